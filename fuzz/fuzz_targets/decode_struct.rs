@@ -0,0 +1,8 @@
+#![no_main]
+
+use jce_fuzz::{round_trip, Everything};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    round_trip::<Everything>(data);
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // the first byte is the type to skip
+    if let Some((&t, mut buf)) = data.split_first() {
+        let _ = jce::types::skip_field(&mut buf, t);
+    }
+});
@@ -0,0 +1,63 @@
+use bytes::Bytes;
+use jce::JceStruct;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// A struct with every supported field type.
+#[derive(JceStruct, PartialEq, Debug)]
+pub struct Everything {
+    pub i8: i8,
+    pub u8: u8,
+    pub i16: i16,
+    pub u16: u16,
+    pub i32: i32,
+    pub u32: u32,
+    pub i64: i64,
+    pub u64: u64,
+    pub f32: f32,
+    pub f64: f64,
+    pub bool: bool,
+    pub string: String,
+    pub vec_u8: Vec<u8>,
+    pub bytes: Bytes,
+    pub array: [u8; 4],
+    pub list: Vec<i32>,
+    pub strings: Vec<String>,
+    pub structs: Vec<Nested>,
+    pub lists: Vec<Vec<i64>>,
+    pub map: HashMap<String, i32>,
+    pub map_bytes: HashMap<i32, Vec<u8>>,
+    pub option: Option<i32>,
+    pub option_struct: Option<Nested>,
+    pub nested: Nested,
+    #[jce(tag = "200")]
+    pub tag_200: String,
+}
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+pub struct Nested {
+    pub a: i64,
+    pub b: String,
+    pub c: Vec<Nested>,
+}
+
+/// Decodes `data`, and if it decodes, checks that encoding it again
+/// gives a value that decodes to the same thing.
+pub fn round_trip<T: JceStruct + PartialEq + Debug>(data: &[u8]) {
+    let val = match T::decode(data) {
+        Ok(val) => val,
+        Err(_) => return,
+    };
+
+    let mut buf = vec![];
+    val.encode(&mut buf).unwrap();
+    assert_eq!(val.encoded_len(), Ok(buf.len()));
+
+    let decoded = T::decode(&*buf).unwrap();
+
+    // NaN is never equal to itself
+    #[allow(clippy::eq_op)]
+    if val == val {
+        assert_eq!(val, decoded);
+    }
+}
@@ -0,0 +1,66 @@
+use proc_macro2::Span;
+use quote::ToTokens;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{Attribute, Error, Expr, ExprLit, Lit, Path, Token};
+
+/// Calls `f` with every item of every `#[jce(...)]` in `attrs`.
+pub fn parse(
+    attrs: &[Attribute],
+    mut f: impl FnMut(ParseNestedMeta) -> syn::Result<()>,
+) -> syn::Result<()> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("jce")) {
+        attr.parse_nested_meta(&mut f)?;
+    }
+
+    Ok(())
+}
+
+/// An item without a value, like `required`.
+pub fn flag(meta: &ParseNestedMeta) -> syn::Result<Span> {
+    if meta.input.peek(Token![=]) {
+        return Err(meta.error(format!("`{}` takes no value", name(meta))));
+    }
+
+    Ok(meta.path.span())
+}
+
+/// The value of `key = value`.
+pub fn value(meta: &ParseNestedMeta) -> syn::Result<Expr> {
+    meta.value()?.parse()
+}
+
+/// A tag, either `5` or `"5"`.
+pub fn tag(meta: &ParseNestedMeta) -> syn::Result<u8> {
+    let expr = value(meta)?;
+    let tag = match &expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(i), ..
+        }) => i.base10_parse().ok(),
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => s.value().parse().ok(),
+        _ => None,
+    };
+
+    tag.ok_or_else(|| Error::new_spanned(expr, "tag must be an integer in 0..=255"))
+}
+
+/// A path, either `a::b` or `"a::b"`.
+pub fn path(meta: &ParseNestedMeta, expected: &str) -> syn::Result<Path> {
+    match value(meta)? {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => s.parse(),
+        Expr::Path(p) => Ok(p.path),
+        expr => Err(Error::new_spanned(expr, expected)),
+    }
+}
+
+pub fn unknown(meta: &ParseNestedMeta) -> Error {
+    meta.error(format!("unknown jce attribute `{}`", name(meta)))
+}
+
+fn name(meta: &ParseNestedMeta) -> String {
+    meta.path.to_token_stream().to_string()
+}
@@ -0,0 +1,158 @@
+use proc_macro2::TokenStream;
+use std::collections::HashSet;
+use syn::visit::{self, Visit};
+use syn::visit_mut::VisitMut;
+use syn::{
+    parse_quote, GenericArgument, Generics, Ident, Lifetime, PathArguments, Type, TypePath,
+    WherePredicate,
+};
+
+/// Bounds on the type parameters used by the fields, like serde infers them.
+pub struct Bounds<'a> {
+    params: HashSet<&'a Ident>,
+    predicates: Vec<WherePredicate>,
+}
+
+impl<'a> Bounds<'a> {
+    pub fn new(generics: &'a Generics) -> Self {
+        Self {
+            params: generics.type_params().map(|p| &p.ident).collect(),
+            predicates: vec![],
+        }
+    }
+
+    /// Whether `ty` is a type parameter or an associated type of one, or one in `Option` or `Vec`,
+    /// which only need it to be `JceTypeRef` to be read borrowed.
+    pub fn is_param_ref(&self, ty: &Type) -> bool {
+        if self.param(ty).is_some() {
+            return true;
+        }
+
+        let segment = match ty {
+            Type::Path(TypePath { qself: None, path }) => path.segments.last(),
+            _ => None,
+        };
+
+        match segment {
+            Some(segment) if segment.ident == "Option" || segment.ident == "Vec" => {
+                match &segment.arguments {
+                    PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                        match &args.args[0] {
+                            GenericArgument::Type(ty) => self.is_param_ref(ty),
+                            _ => false,
+                        }
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `ty` uses a type parameter, lifetimes don't count.
+    pub fn uses_params(&self, ty: &Type) -> bool {
+        !self.used(ty).is_empty()
+    }
+
+    /// Bounds every type parameter, or associated type of one, used in `ty`.
+    pub fn add(&mut self, ty: &Type, bound: &TokenStream) {
+        for ty in self.used(ty) {
+            self.push(&ty, bound);
+        }
+    }
+
+    /// Bounds `ty` if it is a type parameter or an associated type of one,
+    /// `Vec<T>: Default` needs nothing from `T`.
+    pub fn add_bare(&mut self, ty: &Type, bound: &TokenStream) {
+        if let Some(ty) = self.param(ty) {
+            self.push(&ty, bound);
+        }
+    }
+
+    pub fn into_generics(self, generics: &Generics) -> Generics {
+        with_predicates(generics, self.predicates)
+    }
+
+    fn used(&self, ty: &Type) -> Vec<Type> {
+        let mut used = Used {
+            params: &self.params,
+            used: vec![],
+        };
+        used.visit_type(ty);
+        used.used
+    }
+
+    /// `ty` if it is a type parameter, or a path like `T::Item` starting with one.
+    fn param(&self, ty: &Type) -> Option<Type> {
+        match ty {
+            Type::Path(path) => bounded(&self.params, path),
+            Type::Group(group) => self.param(&group.elem),
+            Type::Paren(paren) => self.param(&paren.elem),
+            _ => None,
+        }
+    }
+
+    fn push(&mut self, ty: &Type, bound: &TokenStream) {
+        let predicate: WherePredicate = parse_quote!(#ty: #bound);
+        if !self.predicates.contains(&predicate) {
+            self.predicates.push(predicate);
+        }
+    }
+}
+
+pub fn with_predicates(
+    generics: &Generics,
+    predicates: impl IntoIterator<Item = WherePredicate>,
+) -> Generics {
+    let mut generics = generics.clone();
+    generics.make_where_clause().predicates.extend(predicates);
+    generics
+}
+
+/// `ty` with every lifetime made `'static`, to name it outside the impl.
+pub fn static_lifetimes(ty: &Type) -> Type {
+    struct Static;
+
+    impl VisitMut for Static {
+        fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+            *lifetime = parse_quote!('static);
+        }
+    }
+
+    let mut ty = ty.clone();
+    Static.visit_type_mut(&mut ty);
+    ty
+}
+
+/// The type to bound for `ty`, `T` itself or the whole `T::Item` like serde.
+fn bounded(params: &HashSet<&Ident>, ty: &TypePath) -> Option<Type> {
+    if ty.qself.is_some() || ty.path.leading_colon.is_some() {
+        return None;
+    }
+
+    let first = ty.path.segments.first()?;
+    if !params.contains(&first.ident) || !first.arguments.is_none() {
+        return None;
+    }
+
+    Some(Type::Path(ty.clone()))
+}
+
+/// Collects the bounded types in a type, `T` of `Vec<T>` or `T::Item`.
+struct Used<'a, 'p> {
+    params: &'p HashSet<&'a Ident>,
+    used: Vec<Type>,
+}
+
+impl<'ast> Visit<'ast> for Used<'_, '_> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        match bounded(self.params, ty) {
+            Some(ty) => {
+                if !self.used.contains(&ty) {
+                    self.used.push(ty);
+                }
+            }
+            None => visit::visit_type_path(self, ty),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::attr;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Attribute, DataEnum, Error, Expr, ExprLit, ExprUnary, Fields, Ident, Lit, UnOp};
+
+pub fn try_jce_enum(name: Ident, attrs: &[Attribute], e: DataEnum) -> syn::Result<TokenStream> {
+    let mut krate = None;
+    attr::parse(attrs, |meta| {
+        if meta.path.is_ident("crate") {
+            krate = Some(attr::path(&meta, "expected a crate path")?);
+            Ok(())
+        } else {
+            Err(attr::unknown(&meta))
+        }
+    })?;
+    let krate = crate::krate(krate.as_ref());
+
+    let mut reads = vec![];
+    let mut values = vec![];
+    let mut other = None;
+
+    let mut value: i64 = -1;
+    for variant in &e.variants {
+        let ident = &variant.ident;
+        let mut raw = false;
+
+        if let Some(attr) = other_attr(&variant.attrs)? {
+            if other.is_some() {
+                return Err(Error::new(attr, "only one variant can be #[jce(other)]"));
+            }
+
+            match &variant.fields {
+                // catch-all, written with its own discriminant
+                Fields::Unit => other = Some(quote!(_ => Self::#ident)),
+                // keeps the raw value
+                Fields::Unnamed(f) if f.unnamed.len() == 1 => {
+                    other = Some(quote!(value => Self::#ident(value)));
+                    values.push(quote!(Self::#ident(value) => *value));
+                    raw = true;
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        variant,
+                        "#[jce(other)] variant must be a unit or have one field",
+                    ))
+                }
+            }
+        } else if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "JceType can only derive for fieldless enum",
+            ));
+        }
+
+        value = match &variant.discriminant {
+            Some((_, expr)) => discriminant(expr)?,
+            None => value + 1,
+        };
+
+        // it still takes a discriminant, which the next variant counts from
+        if raw {
+            continue;
+        }
+
+        let v = i32::try_from(value)
+            .map_err(|_| Error::new_spanned(variant, "discriminant overflows i32"))?;
+        reads.push(quote!(#v => Self::#ident));
+        values.push(quote!(Self::#ident => #v));
+    }
+
+    let other = other.unwrap_or_else(|| {
+        quote! {
+            value => return ::core::result::Result::Err(
+                #krate::error::DecodeError::UnknownDiscriminant {
+                    struct_name,
+                    field,
+                    value,
+                }
+            )
+        }
+    });
+
+    Ok(quote! {
+        impl #krate::types::JceType for #name {
+            fn read<__B: #krate::bytes::Buf>(
+                __jce_buf: &mut __B,
+                __jce_t: u8,
+                struct_name: &'static str,
+                field: &'static str,
+            ) -> #krate::error::DecodeResult<Self> {
+                let value = <i32 as #krate::types::JceType>::read(__jce_buf, __jce_t, struct_name, field)?;
+
+                ::core::result::Result::Ok(match value {
+                    #(#reads,)*
+                    #other,
+                })
+            }
+
+            fn write<__B: #krate::bytes::BufMut>(&self, __jce_buf: &mut __B, tag: u8) {
+                let value: i32 = match self {
+                    #(#values),*
+                };
+
+                #krate::types::JceType::write(&value, __jce_buf, tag);
+            }
+
+            fn write_len(&self) -> #krate::error::EncodeResult<usize> {
+                let value: i32 = match self {
+                    #(#values),*
+                };
+
+                #krate::types::JceType::write_len(&value)
+            }
+        }
+
+        #krate::impl_type_ref!(#name);
+    })
+}
+
+/// Finds `#[jce(other)]`.
+fn other_attr(attrs: &[Attribute]) -> syn::Result<Option<Span>> {
+    let mut other = None;
+
+    attr::parse(attrs, |meta| {
+        if meta.path.is_ident("other") {
+            other = Some(attr::flag(&meta)?);
+            Ok(())
+        } else {
+            Err(attr::unknown(&meta))
+        }
+    })?;
+
+    Ok(other)
+}
+
+fn discriminant(expr: &Expr) -> syn::Result<i64> {
+    let value = match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(i), ..
+        }) => i.base10_parse()?,
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => -discriminant(expr)?,
+        _ => {
+            return Err(Error::new_spanned(
+                expr,
+                "discriminant must be an integer literal",
+            ))
+        }
+    };
+
+    Ok(value)
+}
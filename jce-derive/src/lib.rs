@@ -1,159 +1,703 @@
 use proc_macro::TokenStream;
-use proc_macro2::TokenTree;
-use quote::quote;
-use std::error::Error;
-use syn::{Data, DeriveInput};
-
-macro_rules! error {
-    ($e:expr) => {
-        return Err($e.into())
-    };
-}
+use proc_macro2::Span;
+use quote::{format_ident, quote, ToTokens};
+use std::collections::HashMap;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, ExprLit, ExprPath, Fields, Generics, Ident, Index,
+    Lit, Member, Path, Token, Type, WherePredicate,
+};
+
+mod attr;
+mod bound;
+mod enums;
+mod transparent;
 
 #[proc_macro_derive(JceStruct, attributes(jce))]
 pub fn jce(input: TokenStream) -> TokenStream {
-    try_jce(input).unwrap()
+    try_jce(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(JceType, attributes(jce))]
+pub fn jce_type(input: TokenStream) -> TokenStream {
+    try_jce_type(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
 }
 
-fn try_jce(input: TokenStream) -> Result<TokenStream, Box<dyn Error>> {
+fn try_jce_type(input: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let input: DeriveInput = syn::parse(input)?;
+
+    match input.data {
+        Data::Enum(e) => enums::try_jce_enum(input.ident, &input.attrs, e),
+        _ => Err(Error::new_spanned(
+            input.ident,
+            "JceType can only derive for enum",
+        )),
+    }
+}
+
+#[derive(Default)]
+struct StructAttrs {
+    default: bool,
+    omit_defaults: bool,
+    transparent: bool,
+    krate: Option<Path>,
+    bound: Option<Vec<WherePredicate>>,
+}
+
+impl StructAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut this = Self::default();
+
+        attr::parse(attrs, |meta| {
+            if meta.path.is_ident("default") {
+                attr::flag(&meta)?;
+                this.default = true;
+            } else if meta.path.is_ident("omit_defaults") {
+                attr::flag(&meta)?;
+                this.omit_defaults = true;
+            } else if meta.path.is_ident("transparent") {
+                attr::flag(&meta)?;
+                this.transparent = true;
+            } else if meta.path.is_ident("crate") {
+                this.krate = Some(attr::path(&meta, "expected a crate path")?);
+            } else if meta.path.is_ident("bound") {
+                // replaces the inferred bounds, `bound = ""` for none
+                this.bound = Some(match attr::value(&meta)? {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(s), ..
+                    }) => s
+                        .parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?
+                        .into_iter()
+                        .collect(),
+                    expr => return Err(Error::new_spanned(expr, "expected a string of bounds")),
+                });
+            } else {
+                return Err(attr::unknown(&meta));
+            }
+
+            Ok(())
+        })?;
+
+        Ok(this)
+    }
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    tag: Option<u8>,
+    required: bool,
+    default: Option<proc_macro2::TokenStream>,
+    skip: Option<Span>,
+    with: Option<Path>,
+    unknown_fields: Option<Span>,
+    flatten: Option<Span>,
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut this = Self::default();
+
+        attr::parse(attrs, |meta| {
+            if meta.path.is_ident("tag") {
+                if this.tag.is_some() {
+                    return Err(meta.error("duplicate tag"));
+                }
+
+                this.tag = Some(attr::tag(&meta)?);
+            } else if meta.path.is_ident("required") {
+                attr::flag(&meta)?;
+                this.required = true;
+            } else if meta.path.is_ident("default") {
+                // a string is the path of a function, like serde
+                this.default = Some(match attr::value(&meta)? {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(s), ..
+                    }) => {
+                        let path: ExprPath = s.parse()?;
+                        quote!(#path())
+                    }
+                    expr => quote!(#expr),
+                });
+            } else if meta.path.is_ident("with") {
+                this.with = Some(attr::path(&meta, "expected a module path")?);
+            } else if meta.path.is_ident("unknown_fields") {
+                this.unknown_fields = Some(attr::flag(&meta)?);
+            } else if meta.path.is_ident("flatten") {
+                this.flatten = Some(attr::flag(&meta)?);
+            } else if meta.path.is_ident("skip") {
+                // `skip = "expr"` gives the value of the field
+                if meta.input.peek(Token![=]) {
+                    let expr = match attr::value(&meta)? {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Str(s), ..
+                        }) => s.parse()?,
+                        expr => expr,
+                    };
+                    this.default = Some(quote!(#expr));
+                }
+                this.skip = Some(meta.path.span());
+            } else {
+                return Err(attr::unknown(&meta));
+            }
+
+            Ok(())
+        })?;
+
+        if let (true, Some(default)) = (this.required, &this.default) {
+            return Err(Error::new_spanned(
+                default,
+                "required field can't have a default",
+            ));
+        }
+
+        if let Some(skip) = this.skip {
+            if this.tag.is_some() || this.required {
+                return Err(Error::new(
+                    skip,
+                    "skipped field can't have a tag or be required",
+                ));
+            }
+        }
+
+        let others = this.tag.is_some()
+            || this.required
+            || this.default.is_some()
+            || this.skip.is_some()
+            || this.with.is_some();
+
+        if let Some(unknown) = this.unknown_fields {
+            if others || this.flatten.is_some() {
+                return Err(Error::new(
+                    unknown,
+                    "`unknown_fields` can't be used with other attributes",
+                ));
+            }
+        }
+
+        if let Some(flatten) = this.flatten {
+            if others {
+                return Err(Error::new(
+                    flatten,
+                    "`flatten` can't be used with other attributes",
+                ));
+            }
+        }
+
+        Ok(this)
+    }
+}
+
+/// Adds `'__de`, outliving every lifetime of a borrowed struct.
+fn de_generics(generics: &Generics) -> Generics {
+    let mut generics = generics.clone();
+    let lifetimes: Vec<_> = generics.lifetimes().map(|l| l.lifetime.clone()).collect();
+    generics
+        .params
+        .insert(0, syn::parse_quote!('__de: #(#lifetimes)+*));
+    generics
+}
+
+fn slot(member: &Member) -> Ident {
+    match member {
+        Member::Named(ident) => format_ident!("__jce_field_{}", ident),
+        Member::Unnamed(index) => format_ident!("__jce_field_{}", index.index),
+    }
+}
+
+/// The path of the `jce` crate in generated code, `::jce` unless overridden.
+fn krate(path: Option<&Path>) -> proc_macro2::TokenStream {
+    match path {
+        Some(path) => quote!(#path),
+        None => quote!(::jce),
+    }
+}
+
+fn try_jce(input: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
     let input: DeriveInput = syn::parse(input)?;
 
     let s = match input.data {
         Data::Struct(s) => s,
-        _ => error!("JceStruct can only derive for struct"),
+        _ => {
+            return Err(Error::new_spanned(
+                input.ident,
+                "JceStruct can only derive for struct",
+            ))
+        }
     };
 
-    let (imp_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    if let Fields::Unit = s.fields {
+        return Err(Error::new_spanned(
+            input.ident,
+            "JceStruct can't derive for unit struct",
+        ));
+    }
+
+    let attrs = StructAttrs::parse(&input.attrs)?;
+    let krate = krate(attrs.krate.as_ref());
+
+    if attrs.transparent {
+        if attrs.default || attrs.omit_defaults {
+            return Err(Error::new_spanned(
+                input.ident,
+                "#[jce(transparent)] can't be used with other attributes",
+            ));
+        }
+
+        return transparent::try_jce_transparent(
+            &krate,
+            input.ident,
+            &input.generics,
+            attrs.bound,
+            s,
+        );
+    }
 
     let name = input.ident;
 
-    let default = quote! { ::core::default::Default::default() };
+    // structs with lifetimes borrow from a `&'__de [u8]` input
+    let borrowed = input.generics.lifetimes().next().is_some();
+    let (read, write, field_len) = if borrowed {
+        (
+            quote!(#krate::types::JceTypeRef::read_ref),
+            quote!(#krate::types::JceTypeRef::write_field_ref),
+            quote!(#krate::types::JceTypeRef::field_len_ref),
+        )
+    } else {
+        (
+            quote!(#krate::types::JceType::read),
+            quote!(#krate::types::JceType::write_field),
+            quote!(#krate::types::JceType::field_len),
+        )
+    };
+
     let mut fields_default: Vec<proc_macro2::TokenStream> = vec![];
 
     let mut fields_encoded_len: Vec<proc_macro2::TokenStream> = vec![];
 
-    let mut tags: Vec<u8> = vec![];
+    let mut fields = vec![];
+    let mut fields_init = vec![];
+    let mut used: HashMap<u8, &Member> = HashMap::new();
+    let mut unknown = None;
+    let mut bounds = bound::Bounds::new(&input.generics);
+    let mut default_bounds = bound::Bounds::new(&input.generics);
+    // the tags can only be checked where the flattened types are known
+    let mut generic_tags = false;
+
+    // tuple structs are numbered like named fields
+    let members: Vec<Member> = s
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        })
+        .collect();
+
+    let mut next_tag = Some(0u8);
+    let mut after_flatten = None;
+    for (field, ident) in s.fields.iter().zip(&members) {
+        let ty = &field.ty;
+        let span: &dyn ToTokens = match &field.ident {
+            Some(ident) => ident,
+            None => ty,
+        };
+
+        let field_attrs = FieldAttrs::parse(&field.attrs)?;
 
-    let mut tag: i32 = -1;
-    for field in &s.fields {
-        let ident = &field.ident;
+        let default = field_attrs
+            .default
+            .clone()
+            .unwrap_or_else(|| quote!(<#ty as ::core::default::Default>::default()));
         fields_default.push(quote!(#ident: #default));
 
-        fields_encoded_len.push(quote!(::jce::types::JceType::write_len(&self.#ident)));
+        if field_attrs.default.is_none() {
+            let default = quote!(::core::default::Default);
+            default_bounds.add_bare(ty, &default);
+            // a missing field falls back to it
+            if !field_attrs.required
+                && field_attrs.flatten.is_none()
+                && field_attrs.unknown_fields.is_none()
+            {
+                bounds.add_bare(ty, &default);
+            }
+        }
 
-        if field.attrs.is_empty() {
-            tag += 1;
-            tags.push(tag as u8);
+        if field_attrs.skip.is_some() {
+            fields_init.push(quote!(#ident: #default));
             continue;
         }
 
-        for attr in &field.attrs {
-            if attr
-                .path
-                .segments
-                .iter()
-                .find(|seg| seg.ident == "jce")
-                .is_some()
-            {
-                if let Some(tt) = attr.tokens.clone().into_iter().next() {
-                    match tt {
-                        TokenTree::Group(e) => {
-                            let mut stream = e.stream().into_iter();
-
-                            match (stream.next(), stream.next()) {
-                                (Some(TokenTree::Ident(ident)), Some(TokenTree::Punct(punct))) => {
-                                    if ident != "tag" || punct.as_char() != '=' {
-                                        error!("tag error");
-                                    }
-                                }
-                                _ => error!("attribute error"),
-                            }
-
-                            tag = if let Some(TokenTree::Literal(lit)) = stream.next() {
-                                let str = lit.to_string();
-                                <u8 as std::str::FromStr>::from_str(&str[1..str.len() - 1])?
-                            } else {
-                                error!("tag error");
-                            } as i32;
-
-                            tags.push(tag as u8);
-                        }
-                        _ => error!("wrong attribute"),
-                    }
-                }
-                break;
+        if let Some(span) = field_attrs.unknown_fields {
+            if unknown.is_some() {
+                return Err(Error::new(
+                    span,
+                    "only one field can be #[jce(unknown_fields)]",
+                ));
+            }
+
+            unknown = Some((ident, ty));
+            continue;
+        }
+
+        // the fields of a flattened struct take its place in the tag space
+        if field_attrs.flatten.is_some() {
+            generic_tags |= bounds.uses_params(ty);
+            bounds.add(ty, &quote!(#krate::types::Flatten));
+            fields.push((ident, field, None, field_attrs, default));
+            after_flatten = Some(ident);
+            continue;
+        }
+
+        if field_attrs.with.is_none() {
+            // `Option` and `Vec` borrow through `JceTypeRef`, other containers need `JceType`
+            let codec = if borrowed && bounds.is_param_ref(ty) {
+                quote!(#krate::types::JceTypeRef<'__de>)
+            } else {
+                quote!(#krate::types::JceType)
+            };
+            bounds.add(ty, &codec);
+        }
+
+        if attrs.omit_defaults && !field_attrs.required {
+            bounds.add(ty, &quote!(::core::cmp::PartialEq));
+        }
+
+        // the tags of the flattened struct aren't known here
+        if let (None, Some(flattened)) = (field_attrs.tag, after_flatten) {
+            return Err(Error::new_spanned(
+                span,
+                format!(
+                    "a field after the flattened `{}` needs a tag",
+                    flattened.to_token_stream()
+                ),
+            ));
+        }
+        after_flatten = None;
+
+        let tag = match field_attrs.tag.or(next_tag) {
+            Some(tag) => tag,
+            None => {
+                return Err(Error::new_spanned(
+                    span,
+                    "tag overflows u8, the previous field has tag 255",
+                ))
             }
+        };
+
+        if let Some(other) = used.insert(tag, ident) {
+            return Err(Error::new_spanned(
+                span,
+                format!(
+                    "tag {} is already used by `{}`",
+                    tag,
+                    other.to_token_stream()
+                ),
+            ));
         }
+
+        fields.push((ident, field, Some(tag), field_attrs, default));
+        next_tag = tag.checked_add(1);
     }
 
+    let generics = match attrs.bound {
+        Some(predicates) => bound::with_predicates(&input.generics, predicates),
+        None => bounds.into_generics(&input.generics),
+    };
+    let (imp_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut slots = vec![];
     let mut matches = vec![];
     let mut encodes = vec![];
+    let mut flattened = vec![];
+
+    for (ident, field, tag, field_attrs, default) in fields {
+        let ty = &field.ty;
+        // holds the field until the whole struct is read
+        let slot = slot(ident);
 
-    let tags_encoded_len: usize = tags.iter().map(|tag|
-        if *tag < 0xF {
-            1
-        } else { 2 }
-    ).sum();
+        let tag = match tag {
+            Some(tag) => tag,
+            None => {
+                // collects the fields, decoded once the whole struct is read
+                slots.push(quote!(let mut #slot = ::std::vec::Vec::<u8>::new()));
+                matches.push(quote! {
+                    __jce_tag if <#ty as #krate::types::Flatten>::TAGS.contains(__jce_tag) => {
+                        #krate::types::capture_field(__jce_buf, __jce_tag, __jce_t, &mut #slot)?
+                    }
+                });
+                fields_init.push(quote! {
+                    #ident: <#ty as #krate::JceStruct>::decode_raw(&mut &#slot[..], true)?
+                });
+                encodes.push(quote!(#krate::JceStruct::encode_raw(&self.#ident, __jce_buf);));
+                fields_encoded_len.push(quote!(#krate::JceStruct::encoded_len(&self.#ident)?));
+                flattened.push((ident, ty));
+                continue;
+            }
+        };
 
-    for (i, tag) in tags.into_iter().enumerate() {
-        let ident = &s.fields.iter().nth(i).unwrap().ident;
+        let (field_read, field_write, field_encoded_len) = match &field_attrs.with {
+            Some(with) => (
+                quote!(#with::read),
+                quote!(#with::write(&self.#ident, __jce_buf, #tag)),
+                quote!(#krate::types::header_len(#tag) + #with::write_len(&self.#ident)?),
+            ),
+            None => (
+                read.clone(),
+                quote!(#write(&self.#ident, __jce_buf, #tag)),
+                quote!(#field_len(&self.#ident, #tag)?),
+            ),
+        };
 
         let tag_to = quote!(#tag => );
-        let read = quote!(::jce::types::JceType::read(
-            buf,
-            t,
-            STRUCT_NAME,
-            stringify!(#ident)
+        let read = quote!(#field_read(
+            __jce_buf,
+            __jce_t,
+            __JCE_STRUCT_NAME,
+            ::core::stringify!(#ident)
         )?);
 
-        matches.push(quote!(#tag_to val.#ident = #read));
-        encodes.push(quote!(::jce::types::JceType::write(&self.#ident, buf, #tag)));
+        slots.push(
+            quote!(let mut #slot: ::core::option::Option<#ty> = ::core::option::Option::None),
+        );
+        fields_init.push(if field_attrs.required {
+            quote! {
+                #ident: match #slot {
+                    ::core::option::Option::Some(__jce_v) => __jce_v,
+                    ::core::option::Option::None => {
+                        return ::core::result::Result::Err(
+                            #krate::error::DecodeError::MissingField {
+                                struct_name: __JCE_STRUCT_NAME,
+                                field: ::core::stringify!(#ident),
+                                tag: #tag,
+                            },
+                        )
+                    }
+                }
+            }
+        } else {
+            quote!(#ident: ::core::option::Option::unwrap_or_else(#slot, || #default))
+        });
+        matches.push(quote!(#tag_to #slot = ::core::option::Option::Some(#read)));
+
+        if let Some((unknown, _)) = unknown {
+            // unknown fields with a lower tag go first
+            encodes.push(quote! {
+                #krate::UnknownFields::write_before(&self.#unknown, __jce_buf, &mut __jce_unknown_from, #tag);
+            });
+        }
+
+        if attrs.omit_defaults && !field_attrs.required {
+            encodes.push(quote! {
+                if self.#ident != #default {
+                    #field_write;
+                }
+            });
+            fields_encoded_len.push(quote! {
+                (if self.#ident != #default {
+                    #field_encoded_len
+                } else {
+                    0
+                })
+            });
+        } else {
+            encodes.push(quote!(#field_write;));
+            fields_encoded_len.push(field_encoded_len);
+        }
     }
 
-    Ok(quote! {
-        impl #imp_generics ::jce::JceStruct for #name #ty_generics #where_clause {
-            fn encode_raw<B: ::jce::bytes::BufMut>(&self, buf: &mut B) {
-                #(#encodes);*;
+    let (flattened_ident, flattened_ty): (Vec<_>, Vec<_>) = flattened.into_iter().unzip();
+    let mut skip_unknown = quote!(_ => #krate::types::skip_field(__jce_buf, __jce_t)?);
+
+    let mut own_tags: Vec<_> = used.keys().collect();
+    own_tags.sort();
+    let tags_of = |flattened_ty: &[Type]| {
+        quote! {{
+            let __jce_tags = #krate::types::Tags::new(&[#(#own_tags),*]);
+            #(
+                let __jce_flattened = <#flattened_ty as #krate::types::Flatten>::TAGS;
+                ::core::assert!(
+                    !__jce_tags.overlaps(__jce_flattened),
+                    ::core::concat!(
+                        "tags of flattened field `",
+                        ::core::stringify!(#flattened_ident),
+                        "` collide in ",
+                        ::core::stringify!(#name),
+                    ),
+                );
+                let __jce_tags = __jce_tags.union(__jce_flattened);
+            )*
+            __jce_tags
+        }}
+    };
+    let flattened_ty: Vec<Type> = flattened_ty.into_iter().cloned().collect();
+    let tags = tags_of(&flattened_ty);
+
+    if let Some((unknown, ty)) = unknown {
+        let slot = slot(unknown);
+
+        slots.push(quote!(let mut #slot: #ty = ::core::default::Default::default()));
+        fields_init.push(quote!(#unknown: #slot));
+        skip_unknown = quote!(__jce_tag => #krate::UnknownFields::read_field(&mut #slot, __jce_buf, __jce_tag, __jce_t)?);
+
+        let from = if encodes.is_empty() {
+            quote!(let __jce_unknown_from = 0;)
+        } else {
+            quote!(let mut __jce_unknown_from = 0;)
+        };
+        encodes.insert(0, from);
+        encodes
+            .push(quote!(#krate::UnknownFields::write_rest(&self.#unknown, __jce_buf, __jce_unknown_from);));
+        // a tag the struct writes itself makes the encode fail
+        fields_encoded_len.push(quote!(#krate::UnknownFields::checked_len(&self.#unknown, #tags)?));
+    }
+
+    // fails to compile when the tags collide, structs with flattened type
+    // parameters fail when they are built
+    let flatten = !flattened_ident.is_empty();
+    let check_tags = flatten.then(|| {
+        if borrowed {
+            quote!(let _ = Self::__JCE_TAGS;)
+        } else {
+            quote!(let _ = <Self as #krate::types::Flatten>::TAGS;)
+        }
+    });
+    let check_tags_const = (flatten && !generic_tags).then(|| {
+        // a free const can't name the lifetimes of the struct
+        let flattened_ty: Vec<Type> = flattened_ty.iter().map(bound::static_lifetimes).collect();
+        let tags = tags_of(&flattened_ty);
+        quote! {
+            const _: #krate::types::Tags = #tags;
+        }
+    });
+    // borrowed structs have no `TAGS` to check
+    let tags_imp = (flatten && borrowed).then(|| {
+        quote! {
+            impl #imp_generics #name #ty_generics #where_clause {
+                const __JCE_TAGS: #krate::types::Tags = #tags;
             }
+        }
+    });
+
+    let encode_raw = quote! {
+        fn encode_raw<__B: #krate::bytes::BufMut>(&self, __jce_buf: &mut __B) {
+            #check_tags
+            #(#encodes)*
+        }
+
+        fn encoded_len(&self) -> #krate::error::EncodeResult<usize> {
+            #check_tags
+            ::core::result::Result::Ok(0 #(+ #fields_encoded_len)*)
+        }
+    };
+
+    let decode_body = quote! {
+        const __JCE_STRUCT_NAME: &str = ::core::stringify!(#name);
+
+        #check_tags
+
+        #(#slots;)*
 
-            fn encoded_len(&self) -> usize {
-                #tags_encoded_len + #(#fields_encoded_len)+*
+        let mut __jce_t = 0;
+        while #krate::bytes::Buf::remaining(__jce_buf) > 0 {
+            let __jce_header = #krate::de::read_header(__jce_buf)?;
+
+            __jce_t = __jce_header.value_type();
+            if !__jce_to_end && __jce_t == #krate::types::STRUCT_END {
+                break;
             }
 
-            fn decode_raw<B: ::jce::bytes::Buf>(
-                buf: &mut B,
-                to_end: bool,
-            ) -> ::jce::error::DecodeResult<Self> {
-                const STRUCT_NAME: &str = stringify!(#name);
+            match __jce_header.tag() {
+                #(#matches,)*
+                #skip_unknown,
+            }
+        }
 
-                let mut val = Self::default();
+        ::core::result::Result::Ok(Self {
+            #(#fields_init,)*
+        })
+    };
 
-                let mut t = 0;
-                while buf.remaining() > 0 {
-                    let header = ::jce::de::read_header(buf)?;
+    let imp = if borrowed {
+        let generics = de_generics(&generics);
+        let (de_imp_generics, _, _) = generics.split_for_impl();
 
-                    t = header.value_type();
-                    if !to_end && t == ::jce::types::STRUCT_END {
-                        break;
-                    }
+        quote! {
+            impl #de_imp_generics #krate::JceStructRef<'__de> for #name #ty_generics #where_clause {
+                #encode_raw
 
-                    match header.tag() {
-                        #(#matches),*,
-                        _ => ::jce::types::skip_field(buf, t)?,
-                    }
+                fn decode_raw_ref(
+                    __jce_buf: &mut &'__de [u8],
+                    __jce_to_end: bool,
+                ) -> #krate::error::DecodeResult<Self> {
+                    #decode_body
+                }
+            }
+
+            impl #de_imp_generics #krate::types::JceTypeRef<'__de> for #name #ty_generics #where_clause {
+                fn read_ref(
+                    __jce_buf: &mut &'__de [u8],
+                    __jce_t: u8,
+                    struct_name: &'static str,
+                    field: &'static str,
+                ) -> #krate::error::DecodeResult<Self> {
+                    #krate::types::read_struct_ref(__jce_buf, __jce_t, struct_name, field)
+                }
+
+                fn write_ref<__B: #krate::bytes::BufMut>(&self, __jce_buf: &mut __B, tag: u8) {
+                    #krate::types::write_struct_ref(self, __jce_buf, tag);
                 }
 
-                Ok(val)
+                fn write_len_ref(&self) -> #krate::error::EncodeResult<usize> {
+                    ::core::result::Result::Ok(<Self as #krate::JceStructRef<'__de>>::encoded_len(self)? + 1)
+                }
             }
         }
+    } else {
+        quote! {
+            impl #imp_generics #krate::JceStruct for #name #ty_generics #where_clause {
+                #encode_raw
 
-        impl #imp_generics ::core::default::Default for #name #ty_generics #where_clause {
-            fn default() -> Self {
-                Self {
-                    #(#fields_default),*,
+                fn decode_raw<__B: #krate::bytes::Buf>(
+                    __jce_buf: &mut __B,
+                    __jce_to_end: bool,
+                ) -> #krate::error::DecodeResult<Self> {
+                    #decode_body
                 }
             }
+
+            impl #imp_generics #krate::types::Flatten for #name #ty_generics #where_clause {
+                const TAGS: #krate::types::Tags = #tags;
+            }
         }
-    }
-    .into())
+    };
+
+    let default_generics = default_bounds.into_generics(&input.generics);
+    let default_imp = attrs.default.then(|| {
+        let (imp_generics, _, where_clause) = default_generics.split_for_impl();
+        quote! {
+            impl #imp_generics ::core::default::Default for #name #ty_generics #where_clause {
+                fn default() -> Self {
+                    Self {
+                        #(#fields_default,)*
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #imp
+
+        #default_imp
+
+        #tags_imp
+
+        #check_tags_const
+    })
 }
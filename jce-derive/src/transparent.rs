@@ -0,0 +1,202 @@
+use crate::bound::{self, Bounds};
+use crate::de_generics;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DataStruct, Error, Generics, Ident, Index, Member, WherePredicate};
+
+/// A struct with one field, written exactly like the field.
+pub fn try_jce_transparent(
+    krate: &TokenStream,
+    name: Ident,
+    generics: &Generics,
+    bound: Option<Vec<WherePredicate>>,
+    s: DataStruct,
+) -> syn::Result<TokenStream> {
+    let mut fields = s.fields.iter();
+    let field = match (fields.next(), fields.next()) {
+        (Some(field), None) => field,
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "#[jce(transparent)] struct must have exactly one field",
+            ))
+        }
+    };
+
+    if let Some(attr) = field.attrs.iter().find(|attr| attr.path().is_ident("jce")) {
+        return Err(Error::new_spanned(
+            attr,
+            "field of a #[jce(transparent)] struct can't have jce attributes",
+        ));
+    }
+
+    let ty = &field.ty;
+    let member = match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(0)),
+    };
+
+    let borrowed = generics.lifetimes().next().is_some();
+    let generics = match bound {
+        Some(predicates) => bound::with_predicates(generics, predicates),
+        None => {
+            let mut bounds = Bounds::new(generics);
+            if borrowed && bounds.is_param_ref(ty) {
+                bounds.add(ty, &quote!(#krate::types::JceTypeRef<'__de>));
+            } else {
+                bounds.add(ty, &quote!(#krate::types::JceType));
+            }
+            bounds.into_generics(generics)
+        }
+    };
+    let (imp_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    if borrowed {
+        let generics = de_generics(&generics);
+        let (de_imp_generics, _, _) = generics.split_for_impl();
+
+        return Ok(quote! {
+            impl #de_imp_generics #krate::types::JceTypeRef<'__de> for #name #ty_generics #where_clause {
+                fn read_ref(
+                    __jce_buf: &mut &'__de [u8],
+                    __jce_t: u8,
+                    struct_name: &'static str,
+                    field: &'static str,
+                ) -> #krate::error::DecodeResult<Self> {
+                    ::core::result::Result::Ok(Self {
+                        #member: <#ty as #krate::types::JceTypeRef<'__de>>::read_ref(
+                            __jce_buf,
+                            __jce_t,
+                            struct_name,
+                            field,
+                        )?,
+                    })
+                }
+
+                fn write_ref<__B: #krate::bytes::BufMut>(&self, __jce_buf: &mut __B, tag: u8) {
+                    #krate::types::JceTypeRef::write_ref(&self.#member, __jce_buf, tag);
+                }
+
+                fn write_len_ref(&self) -> #krate::error::EncodeResult<usize> {
+                    #krate::types::JceTypeRef::write_len_ref(&self.#member)
+                }
+
+                fn write_field_ref<__B: #krate::bytes::BufMut>(&self, __jce_buf: &mut __B, tag: u8) {
+                    #krate::types::JceTypeRef::write_field_ref(&self.#member, __jce_buf, tag);
+                }
+
+                fn field_len_ref(&self, tag: u8) -> #krate::error::EncodeResult<usize> {
+                    #krate::types::JceTypeRef::field_len_ref(&self.#member, tag)
+                }
+
+                fn read_list_ref(
+                    __jce_buf: &mut &'__de [u8],
+                    __jce_t: u8,
+                    struct_name: &'static str,
+                    field: &'static str,
+                ) -> #krate::error::DecodeResult<::std::vec::Vec<Self>> {
+                    let list = <#ty as #krate::types::JceTypeRef<'__de>>::read_list_ref(
+                        __jce_buf,
+                        __jce_t,
+                        struct_name,
+                        field,
+                    )?;
+
+                    ::core::result::Result::Ok(list.into_iter().map(|v| Self { #member: v }).collect())
+                }
+
+                fn write_list_ref<__B: #krate::bytes::BufMut>(list: &[Self], __jce_buf: &mut __B, tag: u8) {
+                    <#ty as #krate::types::JceTypeRef<'__de>>::write_list_by_ref(list, |v| &v.#member, __jce_buf, tag);
+                }
+
+                fn list_write_len_ref(list: &[Self]) -> #krate::error::EncodeResult<usize> {
+                    <#ty as #krate::types::JceTypeRef<'__de>>::list_write_len_by_ref(list, |v| &v.#member)
+                }
+
+                fn write_list_by_ref<__B: #krate::bytes::BufMut, __T>(
+                    list: &[__T],
+                    get: impl Fn(&__T) -> &Self,
+                    __jce_buf: &mut __B,
+                    tag: u8,
+                ) {
+                    <#ty as #krate::types::JceTypeRef<'__de>>::write_list_by_ref(list, |v| &get(v).#member, __jce_buf, tag);
+                }
+
+                fn list_write_len_by_ref<__T>(list: &[__T], get: impl Fn(&__T) -> &Self) -> #krate::error::EncodeResult<usize> {
+                    <#ty as #krate::types::JceTypeRef<'__de>>::list_write_len_by_ref(list, |v| &get(v).#member)
+                }
+            }
+        });
+    }
+
+    let mut ref_generics = generics.clone();
+    ref_generics.params.insert(0, syn::parse_quote!('__de));
+    let (ref_imp_generics, _, _) = ref_generics.split_for_impl();
+
+    // lists are written like lists of the field, `Vec<Newtype(u8)>` as bytes
+    Ok(quote! {
+        impl #imp_generics #krate::types::JceType for #name #ty_generics #where_clause {
+            fn read<__B: #krate::bytes::Buf>(
+                __jce_buf: &mut __B,
+                __jce_t: u8,
+                struct_name: &'static str,
+                field: &'static str,
+            ) -> #krate::error::DecodeResult<Self> {
+                ::core::result::Result::Ok(Self {
+                    #member: <#ty as #krate::types::JceType>::read(__jce_buf, __jce_t, struct_name, field)?,
+                })
+            }
+
+            fn write<__B: #krate::bytes::BufMut>(&self, __jce_buf: &mut __B, tag: u8) {
+                #krate::types::JceType::write(&self.#member, __jce_buf, tag);
+            }
+
+            fn write_len(&self) -> #krate::error::EncodeResult<usize> {
+                #krate::types::JceType::write_len(&self.#member)
+            }
+
+            fn write_field<__B: #krate::bytes::BufMut>(&self, __jce_buf: &mut __B, tag: u8) {
+                #krate::types::JceType::write_field(&self.#member, __jce_buf, tag);
+            }
+
+            fn field_len(&self, tag: u8) -> #krate::error::EncodeResult<usize> {
+                #krate::types::JceType::field_len(&self.#member, tag)
+            }
+
+            fn read_list<__B: #krate::bytes::Buf>(
+                __jce_buf: &mut __B,
+                __jce_t: u8,
+                struct_name: &'static str,
+                field: &'static str,
+            ) -> #krate::error::DecodeResult<::std::vec::Vec<Self>> {
+                let list = <#ty as #krate::types::JceType>::read_list(__jce_buf, __jce_t, struct_name, field)?;
+                ::core::result::Result::Ok(list.into_iter().map(|v| Self { #member: v }).collect())
+            }
+
+            fn write_list<__B: #krate::bytes::BufMut>(list: &[Self], __jce_buf: &mut __B, tag: u8) {
+                <#ty as #krate::types::JceType>::write_list_by(list, |v| &v.#member, __jce_buf, tag);
+            }
+
+            fn list_write_len(list: &[Self]) -> #krate::error::EncodeResult<usize> {
+                <#ty as #krate::types::JceType>::list_write_len_by(list, |v| &v.#member)
+            }
+
+            fn write_list_by<__B: #krate::bytes::BufMut, __T>(
+                list: &[__T],
+                get: impl Fn(&__T) -> &Self,
+                __jce_buf: &mut __B,
+                tag: u8,
+            ) {
+                <#ty as #krate::types::JceType>::write_list_by(list, |v| &get(v).#member, __jce_buf, tag);
+            }
+
+            fn list_write_len_by<__T>(list: &[__T], get: impl Fn(&__T) -> &Self) -> #krate::error::EncodeResult<usize> {
+                <#ty as #krate::types::JceType>::list_write_len_by(list, |v| &get(v).#member)
+            }
+        }
+
+        impl #ref_imp_generics #krate::types::JceTypeRef<'__de> for #name #ty_generics #where_clause {
+            #krate::__type_ref_methods!('__de);
+        }
+    })
+}
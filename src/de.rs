@@ -2,6 +2,191 @@ use crate::error::{DecodeError, DecodeResult};
 use crate::types::JceHeader;
 use crate::{check_type, types};
 use bytes::Buf;
+use std::cell::Cell;
+
+/// Limits applied while decoding, for input from untrusted peers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// Maximum nesting depth of structs, lists and maps.
+    pub max_depth: usize,
+    /// Maximum element count of a single list or map.
+    pub max_elements: usize,
+    /// Maximum length of a single bytes or string field.
+    pub max_bytes_len: usize,
+    /// Maximum bytes allocated by all lists, maps and bytes of one decode.
+    pub max_alloc: usize,
+}
+
+impl DecodeLimits {
+    pub const fn new() -> Self {
+        Self {
+            max_depth: 100,
+            max_elements: usize::MAX,
+            max_bytes_len: usize::MAX,
+            max_alloc: usize::MAX,
+        }
+    }
+}
+
+impl DecodeLimits {
+    /// The lower of each limit.
+    const fn min(self, other: Self) -> Self {
+        const fn min(a: usize, b: usize) -> usize {
+            if a < b {
+                a
+            } else {
+                b
+            }
+        }
+
+        Self {
+            max_depth: min(self.max_depth, other.max_depth),
+            max_elements: min(self.max_elements, other.max_elements),
+            max_bytes_len: min(self.max_bytes_len, other.max_bytes_len),
+            max_alloc: min(self.max_alloc, other.max_alloc),
+        }
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct DecodeState {
+    limits: DecodeLimits,
+    depth: usize,
+    alloc: usize,
+    // inside a decode
+    active: bool,
+}
+
+impl DecodeState {
+    const fn new(limits: DecodeLimits) -> Self {
+        Self {
+            limits,
+            depth: 0,
+            alloc: 0,
+            active: false,
+        }
+    }
+}
+
+thread_local! {
+    static STATE: Cell<DecodeState> = const { Cell::new(DecodeState::new(DecodeLimits::new())) };
+}
+
+/// Runs `f` with `limits` applied to every decode on this thread.
+///
+/// A decode nested in another one, like a struct embedded in bytes,
+/// keeps the stricter limits of both, and counts its depth and
+/// allocations with the outer decode.
+pub fn with_limits<R>(limits: DecodeLimits, f: impl FnOnce() -> R) -> R {
+    struct Restore(DecodeState);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            STATE.with(|s| {
+                let mut prev = self.0;
+                if prev.active {
+                    // the outer decode pays for the nested one
+                    prev.alloc = s.get().alloc;
+                }
+                s.set(prev);
+            });
+        }
+    }
+
+    let _restore = Restore(STATE.with(|s| {
+        let prev = s.get();
+        let state = if prev.active {
+            DecodeState {
+                limits: prev.limits.min(limits),
+                ..prev
+            }
+        } else {
+            DecodeState {
+                active: true,
+                ..DecodeState::new(limits)
+            }
+        };
+
+        s.replace(state)
+    }));
+
+    f()
+}
+
+pub(crate) struct DepthGuard(());
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        STATE.with(|s| {
+            let mut state = s.get();
+            state.depth -= 1;
+            s.set(state);
+        });
+    }
+}
+
+/// Enters a nested struct, list or map.
+pub(crate) fn enter() -> DecodeResult<DepthGuard> {
+    STATE.with(|s| {
+        let mut state = s.get();
+        if state.depth >= state.limits.max_depth {
+            return Err(DecodeError::DepthExceeded);
+        }
+
+        state.depth += 1;
+        s.set(state);
+
+        Ok(DepthGuard(()))
+    })
+}
+
+/// Checks the element count of a list or map, and charges `size` bytes
+/// per element to the allocation budget.
+pub(crate) fn alloc_elements(len: usize, size: usize) -> DecodeResult<()> {
+    let max = STATE.with(|s| s.get().limits.max_elements);
+    if len > max {
+        return Err(DecodeError::TooManyElements(len));
+    }
+
+    alloc(len.saturating_mul(size))
+}
+
+/// Checks the length of bytes or a string, and charges it to the allocation budget.
+pub(crate) fn alloc_bytes(len: usize) -> DecodeResult<()> {
+    check_bytes_len(len)?;
+    alloc(len)
+}
+
+/// Checks the length of bytes borrowed from the input, which allocate nothing.
+pub(crate) fn check_bytes_len(len: usize) -> DecodeResult<()> {
+    let max = STATE.with(|s| s.get().limits.max_bytes_len);
+    if len > max {
+        return Err(DecodeError::BytesTooLong(len));
+    }
+
+    Ok(())
+}
+
+/// Charges `size` bytes to the allocation budget.
+pub(crate) fn alloc(size: usize) -> DecodeResult<()> {
+    STATE.with(|s| {
+        let mut state = s.get();
+        state.alloc = state.alloc.saturating_add(size);
+        s.set(state);
+
+        if state.alloc > state.limits.max_alloc {
+            Err(DecodeError::AllocationExceeded)
+        } else {
+            Ok(())
+        }
+    })
+}
 
 #[inline]
 pub(crate) fn check_buf<B: Buf>(buf: &mut B, min: usize) -> DecodeResult<()> {
@@ -12,10 +12,29 @@ pub enum DecodeError {
         field: &'static str,
         val_type: u8,
     },
+    OutOfRange {
+        struct_name: &'static str,
+        field: &'static str,
+        value: i64,
+    },
+    UnknownDiscriminant {
+        struct_name: &'static str,
+        field: &'static str,
+        value: i32,
+    },
+    MissingField {
+        struct_name: &'static str,
+        field: &'static str,
+        tag: u8,
+    },
     InvalidType,
     Eof,
     InvalidLength,
     String(Utf8Error),
+    DepthExceeded,
+    TooManyElements(usize),
+    BytesTooLong(usize),
+    AllocationExceeded,
 }
 
 impl Display for DecodeError {
@@ -37,10 +56,41 @@ impl Display for DecodeError {
 
                 Ok(())
             }
+            Self::OutOfRange {
+                struct_name,
+                field,
+                value,
+            } => write!(
+                f,
+                "value {} out of range for field {} in struct {}",
+                value, field, struct_name
+            ),
+            Self::UnknownDiscriminant {
+                struct_name,
+                field,
+                value,
+            } => write!(
+                f,
+                "unknown discriminant {} of field {} in struct {}",
+                value, field, struct_name
+            ),
+            Self::MissingField {
+                struct_name,
+                field,
+                tag,
+            } => write!(
+                f,
+                "missing required field {} (tag {}) in struct {}",
+                field, tag, struct_name
+            ),
             Self::InvalidType => f.write_str("invalid type"),
             Self::Eof => f.write_str("unexpected eof"),
             Self::InvalidLength => f.write_str("invalid length"),
             Self::String(e) => Display::fmt(e, f),
+            Self::DepthExceeded => f.write_str("nesting depth exceeds limit"),
+            Self::TooManyElements(len) => write!(f, "{} elements exceeds limit", len),
+            Self::BytesTooLong(len) => write!(f, "{} bytes exceeds limit", len),
+            Self::AllocationExceeded => f.write_str("allocation exceeds limit"),
         }
     }
 }
@@ -62,12 +112,35 @@ impl From<FromUtf8Error> for DecodeError {
 
 pub type EncodeResult<T> = Result<T, EncodeError>;
 
-#[derive(Debug)]
-pub struct EncodeError;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeError {
+    InsufficientCapacity {
+        required: usize,
+        available: usize,
+    },
+    /// Bytes or a string longer than `u32::MAX`.
+    BytesTooLong(usize),
+    /// A list or map longer than `i32::MAX`, the reference runtimes read lengths as INT.
+    TooManyElements(usize),
+    /// A tag of `UnknownFields` that the struct writes itself.
+    InvalidTag(u8),
+}
 
 impl Display for EncodeError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str("encode length exceeds buffer")
+        match self {
+            Self::InsufficientCapacity {
+                required,
+                available,
+            } => write!(
+                f,
+                "encode length {} exceeds buffer capacity {}",
+                required, available
+            ),
+            Self::BytesTooLong(len) => write!(f, "{} bytes too long to encode", len),
+            Self::TooManyElements(len) => write!(f, "{} elements too many to encode", len),
+            Self::InvalidTag(tag) => write!(f, "unknown field has tag {} of the struct", tag),
+        }
     }
 }
 
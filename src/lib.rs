@@ -1,3 +1,4 @@
+use crate::de::DecodeLimits;
 use crate::error::{DecodeResult, EncodeError, EncodeResult};
 
 pub mod bytes;
@@ -6,28 +7,74 @@ pub mod error;
 mod macros;
 mod ser;
 pub mod types;
+mod unknown;
 pub mod value;
 
 use ::bytes::{Buf, BufMut};
-pub use jce_derive::JceStruct;
+pub use jce_derive::{JceStruct, JceType};
+pub use unknown::{UnknownField, UnknownFields};
 
 pub trait JceStruct: Sized {
+    /// Writes the fields without checking them, only call it on a value
+    /// whose [`encoded_len`](Self::encoded_len) succeeds.
     fn encode_raw<B: BufMut>(&self, buf: &mut B);
 
     fn encode<B: BufMut>(&self, mut buf: B) -> EncodeResult<()> {
-        if self.encoded_len() > buf.remaining_mut() {
-            return Err(EncodeError);
-        }
-
+        check_capacity(self.encoded_len()?, &buf)?;
         self.encode_raw(&mut buf);
+
         Ok(())
     }
 
-    fn encoded_len(&self) -> usize;
+    /// The length `encode` writes, failing if `self` can't be encoded.
+    fn encoded_len(&self) -> EncodeResult<usize>;
 
     fn decode_raw<B: Buf>(buf: &mut B, to_end: bool) -> DecodeResult<Self>;
 
-    fn decode<B: Buf>(mut buf: B) -> DecodeResult<Self> {
-        Self::decode_raw(&mut buf, true)
+    fn decode<B: Buf>(buf: B) -> DecodeResult<Self> {
+        Self::decode_with_limits(buf, DecodeLimits::default())
+    }
+
+    fn decode_with_limits<B: Buf>(mut buf: B, limits: DecodeLimits) -> DecodeResult<Self> {
+        de::with_limits(limits, || Self::decode_raw(&mut buf, true))
     }
 }
+
+/// A struct that borrows from the input it was decoded from,
+/// derived for structs with lifetime parameters.
+pub trait JceStructRef<'de>: Sized {
+    /// Like [`JceStruct::encode_raw`].
+    fn encode_raw<B: BufMut>(&self, buf: &mut B);
+
+    fn encode<B: BufMut>(&self, mut buf: B) -> EncodeResult<()> {
+        check_capacity(self.encoded_len()?, &buf)?;
+        self.encode_raw(&mut buf);
+
+        Ok(())
+    }
+
+    /// The length `encode` writes, failing if `self` can't be encoded.
+    fn encoded_len(&self) -> EncodeResult<usize>;
+
+    fn decode_raw_ref(buf: &mut &'de [u8], to_end: bool) -> DecodeResult<Self>;
+
+    fn decode_ref(buf: &'de [u8]) -> DecodeResult<Self> {
+        Self::decode_ref_with_limits(buf, DecodeLimits::default())
+    }
+
+    fn decode_ref_with_limits(mut buf: &'de [u8], limits: DecodeLimits) -> DecodeResult<Self> {
+        de::with_limits(limits, || Self::decode_raw_ref(&mut buf, true))
+    }
+}
+
+fn check_capacity<B: BufMut>(required: usize, buf: &B) -> EncodeResult<()> {
+    let available = buf.remaining_mut();
+    if required > available {
+        return Err(EncodeError::InsufficientCapacity {
+            required,
+            available,
+        });
+    }
+
+    Ok(())
+}
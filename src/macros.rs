@@ -4,3 +4,103 @@ macro_rules! check_type {
         $crate::de::check_buf($buf, std::mem::size_of::<$ty>())?
     };
 }
+
+/// Implements [`JceTypeRef`](crate::types::JceTypeRef) for types implementing
+/// [`JceType`](crate::types::JceType), to use them in borrowed structs.
+///
+/// ```
+/// # use jce::bytes::{Buf, BufMut};
+/// # use jce::error::{DecodeResult, EncodeResult};
+/// # use jce::types::JceType;
+/// struct Uin(i64);
+///
+/// impl JceType for Uin {
+///     // ...
+/// #   fn read<B: Buf>(buf: &mut B, t: u8, struct_name: &'static str, field: &'static str) -> DecodeResult<Self> {
+/// #       i64::read(buf, t, struct_name, field).map(Self)
+/// #   }
+/// #   fn write<B: BufMut>(&self, buf: &mut B, tag: u8) {
+/// #       self.0.write(buf, tag)
+/// #   }
+/// #   fn write_len(&self) -> EncodeResult<usize> {
+/// #       self.0.write_len()
+/// #   }
+/// }
+///
+/// jce::impl_type_ref!(Uin);
+/// ```
+#[macro_export]
+macro_rules! impl_type_ref {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<'de> $crate::types::JceTypeRef<'de> for $ty {
+                $crate::__type_ref_methods!('de);
+            }
+        )*
+    };
+}
+
+/// Methods of `JceTypeRef` calling the ones of `JceType`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __type_ref_methods {
+    ($de:lifetime) => {
+        fn read_ref(
+            buf: &mut &$de [u8],
+            t: u8,
+            struct_name: &'static str,
+            field: &'static str,
+        ) -> $crate::error::DecodeResult<Self> {
+            <Self as $crate::types::JceType>::read(buf, t, struct_name, field)
+        }
+
+        fn write_ref<__B: $crate::bytes::BufMut>(&self, buf: &mut __B, tag: u8) {
+            $crate::types::JceType::write(self, buf, tag);
+        }
+
+        fn write_len_ref(&self) -> $crate::error::EncodeResult<usize> {
+            $crate::types::JceType::write_len(self)
+        }
+
+        fn write_field_ref<__B: $crate::bytes::BufMut>(&self, buf: &mut __B, tag: u8) {
+            $crate::types::JceType::write_field(self, buf, tag);
+        }
+
+        fn field_len_ref(&self, tag: u8) -> $crate::error::EncodeResult<usize> {
+            $crate::types::JceType::field_len(self, tag)
+        }
+
+        fn read_list_ref(
+            buf: &mut &$de [u8],
+            t: u8,
+            struct_name: &'static str,
+            field: &'static str,
+        ) -> $crate::error::DecodeResult<::std::vec::Vec<Self>> {
+            <Self as $crate::types::JceType>::read_list(buf, t, struct_name, field)
+        }
+
+        fn write_list_ref<__B: $crate::bytes::BufMut>(list: &[Self], buf: &mut __B, tag: u8) {
+            <Self as $crate::types::JceType>::write_list(list, buf, tag);
+        }
+
+        fn list_write_len_ref(list: &[Self]) -> $crate::error::EncodeResult<usize> {
+            <Self as $crate::types::JceType>::list_write_len(list)
+        }
+
+        fn write_list_by_ref<__B: $crate::bytes::BufMut, __T>(
+            list: &[__T],
+            get: impl Fn(&__T) -> &Self,
+            buf: &mut __B,
+            tag: u8,
+        ) {
+            <Self as $crate::types::JceType>::write_list_by(list, get, buf, tag);
+        }
+
+        fn list_write_len_by_ref<__T>(
+            list: &[__T],
+            get: impl Fn(&__T) -> &Self,
+        ) -> $crate::error::EncodeResult<usize> {
+            <Self as $crate::types::JceType>::list_write_len_by(list, get)
+        }
+    };
+}
@@ -1,3 +1,4 @@
+use crate::error::{EncodeError, EncodeResult};
 use crate::types;
 use crate::types::JceHeader;
 use bytes::BufMut;
@@ -11,6 +12,14 @@ pub fn write_header<B: BufMut>(buf: &mut B, JceHeader { val_type, tag }: JceHead
     }
 }
 
+pub fn header_len(tag: u8) -> usize {
+    if tag < 0xF {
+        1
+    } else {
+        2
+    }
+}
+
 pub fn write_empty<B: BufMut>(buf: &mut B, tag: u8) {
     write_header(
         buf,
@@ -25,38 +34,66 @@ pub fn write_type<B: BufMut>(buf: &mut B, t: u8) {
     buf.put_u8(t);
 }
 
-pub fn write_len<B: BufMut>(buf: &mut B, len: usize) {
-    if let Ok(len) = u8::try_from(len) {
-        buf.put_u8(types::BYTE);
-        buf.put_u8(len);
-    } else if let Ok(len) = u16::try_from(len) {
-        buf.put_u8(types::SHORT);
-        buf.put_u16(len);
-    } else if let Ok(len) = u32::try_from(len) {
-        buf.put_u8(types::INT);
-        buf.put_u32(len);
-    } else if let Ok(len) = u64::try_from(len) {
-        buf.put_u8(types::LONG);
-        buf.put_u64(len);
+/// Writes an integer using the smallest type that can hold it,
+/// like the reference Tars runtimes do: `ZERO_TAG` for `0`,
+/// then `BYTE`, `SHORT`, `INT` and `LONG`.
+pub fn write_int<B: BufMut>(buf: &mut B, tag: u8, val: i64) {
+    let val_type = match int_len(val) {
+        0 => types::ZERO_TAG,
+        1 => types::BYTE,
+        2 => types::SHORT,
+        4 => types::INT,
+        _ => types::LONG,
+    };
+
+    write_header(buf, JceHeader { val_type, tag });
+
+    match val_type {
+        types::ZERO_TAG => {}
+        types::BYTE => buf.put_i8(val as i8),
+        types::SHORT => buf.put_i16(val as i16),
+        types::INT => buf.put_i32(val as i32),
+        _ => buf.put_i64(val),
+    }
+}
+
+/// Bytes written by [`write_int`] after the header.
+pub fn int_len(val: i64) -> usize {
+    if val == 0 {
+        0
+    } else if i8::try_from(val).is_ok() {
+        1
+    } else if i16::try_from(val).is_ok() {
+        2
+    } else if i32::try_from(val).is_ok() {
+        4
     } else {
-        unreachable!();
+        8
     }
 }
 
-pub fn len_bytes(len: usize) -> usize {
-    const U8: usize = u8::MAX as usize;
-    const U81: usize = U8 + 1;
-    const U16: usize = u16::MAX as usize;
-    const U161: usize = U16 + 1;
-    const U32: usize = u32::MAX as usize;
-    const U321: usize = U32 + 1;
-    const U64: usize = u64::MAX as usize;
-
-    match len {
-        0..=U8 => 1,
-        U81..=U16 => 2,
-        U161..=U32 => 4,
-        U321..=U64 => 8,
-        _ => unreachable!(),
+pub fn write_len<B: BufMut>(buf: &mut B, len: usize) {
+    write_int(buf, 0, len as i64);
+}
+
+/// Bytes of a length of a list or map, which has to fit an INT.
+pub fn len_bytes(len: usize) -> EncodeResult<usize> {
+    if i32::try_from(len).is_err() {
+        return Err(EncodeError::TooManyElements(len));
+    }
+
+    Ok(int_len(len as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::EncodeError;
+    use crate::ser::len_bytes;
+
+    #[test]
+    fn too_many_elements() {
+        let len = i32::MAX as usize + 1;
+        assert_eq!(len_bytes(len), Err(EncodeError::TooManyElements(len)));
+        assert_eq!(len_bytes(3), Ok(1));
     }
 }
@@ -1,6 +1,6 @@
-use crate::de::{check_buf, check_buf_zero, read_len};
-use crate::error::{DecodeError, DecodeResult};
-use crate::ser::write_empty;
+use crate::de::{alloc_elements, check_buf, check_buf_zero, enter, read_header, read_len};
+use crate::error::{DecodeError, DecodeResult, EncodeResult};
+use crate::ser::{len_bytes, write_empty, write_header, write_len};
 use bytes::{Buf, BufMut};
 use std::fmt::{Display, Formatter};
 
@@ -16,7 +16,8 @@ pub const MAP: u8 = 8; // Map<*Any, *Any>
 pub const LIST: u8 = 9; // Vec<*Any>
 pub const STRUCT_START: u8 = 10;
 pub const STRUCT_END: u8 = 11;
-pub const EMPTY: u8 = 12; // Option<*Any>
+pub const ZERO_TAG: u8 = 12; // 0 of any integer type
+pub const EMPTY: u8 = ZERO_TAG; // Option<*Any>
 pub const SINGLE_LIST: u8 = 13; // Vec<u8>(?)
 
 fn check_type(
@@ -36,6 +37,43 @@ fn check_type(
     }
 }
 
+/// Reads an integer of any width, sign-extended to `i64`.
+pub(crate) fn read_int<B: Buf>(
+    buf: &mut B,
+    t: u8,
+    struct_name: &'static str,
+    field: &'static str,
+) -> DecodeResult<i64> {
+    let val = match t {
+        ZERO_TAG => 0,
+        BYTE => {
+            check_buf(buf, 1)?;
+            buf.get_i8() as i64
+        }
+        SHORT => {
+            check_buf(buf, 2)?;
+            buf.get_i16() as i64
+        }
+        INT => {
+            check_buf(buf, 4)?;
+            buf.get_i32() as i64
+        }
+        LONG => {
+            check_buf(buf, 8)?;
+            buf.get_i64()
+        }
+        _ => {
+            return Err(DecodeError::IncorrectType {
+                struct_name,
+                field,
+                val_type: t,
+            })
+        }
+    };
+
+    Ok(val)
+}
+
 #[derive(Debug)]
 pub struct JceHeader {
     pub(crate) val_type: u8,
@@ -107,7 +145,250 @@ pub trait JceType: Sized {
 
     fn write<B: BufMut>(&self, buf: &mut B, tag: u8);
 
-    fn write_len(&self) -> usize;
+    /// Length of `self` written after the header, failing if it can't be encoded.
+    fn write_len(&self) -> EncodeResult<usize>;
+
+    /// Writes `self` as a field of a struct, an absent field writes nothing.
+    #[doc(hidden)]
+    fn write_field<B: BufMut>(&self, buf: &mut B, tag: u8) {
+        self.write(buf, tag);
+    }
+
+    /// Length of `self` written as a field of a struct, including the header.
+    #[doc(hidden)]
+    fn field_len(&self, tag: u8) -> EncodeResult<usize> {
+        Ok(header_len(tag) + self.write_len()?)
+    }
+
+    /// Reads a `Vec<Self>`, by default as a LIST with a header per element.
+    #[doc(hidden)]
+    fn read_list<B: Buf>(
+        buf: &mut B,
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<Vec<Self>> {
+        check_type(t, LIST, struct_name, field)?;
+
+        let _guard = enter()?;
+        let len = read_len(buf)?;
+        alloc_elements(len, std::mem::size_of::<Self>())?;
+
+        // each element takes at least one byte, don't trust the length any further
+        let mut v = Vec::with_capacity(len.min(buf.remaining()));
+
+        for _ in 0..len {
+            let t = read_type(buf)?;
+            v.push(Self::read(buf, t, struct_name, field)?);
+        }
+
+        Ok(v)
+    }
+
+    #[doc(hidden)]
+    fn write_list<B: BufMut>(list: &[Self], buf: &mut B, tag: u8) {
+        Self::write_list_by(list, same, buf, tag);
+    }
+
+    #[doc(hidden)]
+    fn list_write_len(list: &[Self]) -> EncodeResult<usize> {
+        Self::list_write_len_by(list, same)
+    }
+
+    /// Writes a list of `Self` taken from `list` by `get`, for newtypes
+    /// which are written like their field in a list too.
+    #[doc(hidden)]
+    fn write_list_by<B: BufMut, T>(list: &[T], get: impl Fn(&T) -> &Self, buf: &mut B, tag: u8) {
+        write_header(
+            buf,
+            JceHeader {
+                val_type: LIST,
+                tag,
+            },
+        );
+
+        write_len(buf, list.len());
+
+        for val in list {
+            get(val).write(buf, 0);
+        }
+    }
+
+    #[doc(hidden)]
+    fn list_write_len_by<T>(list: &[T], get: impl Fn(&T) -> &Self) -> EncodeResult<usize> {
+        Ok(len_bytes(list.len())?
+            + 1 // len type
+            + list
+                .iter()
+                .map(|val| Ok(1 + get(val).write_len()?))
+                .sum::<EncodeResult<usize>>()?)
+    }
+}
+
+fn same<T>(val: &T) -> &T {
+    val
+}
+
+/// A field decoded from a `&'de [u8]` input, which may borrow from it.
+///
+/// Implemented for the [`JceType`]s of this crate and derived ones, for `&str`, `&[u8]`,
+/// `Cow<str>`, `Cow<[u8]>`, derived structs with lifetime parameters, and for `Option`
+/// and `Vec` of all of them. A hand-written [`JceType`] gets it with [`impl_type_ref!`].
+///
+/// [`impl_type_ref!`]: crate::impl_type_ref
+pub trait JceTypeRef<'de>: Sized {
+    fn read_ref(
+        buf: &mut &'de [u8],
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<Self>;
+
+    fn write_ref<B: BufMut>(&self, buf: &mut B, tag: u8);
+
+    fn write_len_ref(&self) -> EncodeResult<usize>;
+
+    #[doc(hidden)]
+    fn write_field_ref<B: BufMut>(&self, buf: &mut B, tag: u8) {
+        self.write_ref(buf, tag);
+    }
+
+    #[doc(hidden)]
+    fn field_len_ref(&self, tag: u8) -> EncodeResult<usize> {
+        Ok(header_len(tag) + self.write_len_ref()?)
+    }
+
+    /// Reads a `Vec<Self>` like [`JceType::read_list`].
+    #[doc(hidden)]
+    fn read_list_ref(
+        buf: &mut &'de [u8],
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<Vec<Self>> {
+        check_type(t, LIST, struct_name, field)?;
+
+        let _guard = enter()?;
+        let len = read_len(buf)?;
+        alloc_elements(len, std::mem::size_of::<Self>())?;
+
+        let mut v = Vec::with_capacity(len.min(buf.remaining()));
+
+        for _ in 0..len {
+            let t = read_type(buf)?;
+            v.push(Self::read_ref(buf, t, struct_name, field)?);
+        }
+
+        Ok(v)
+    }
+
+    #[doc(hidden)]
+    fn write_list_ref<B: BufMut>(list: &[Self], buf: &mut B, tag: u8) {
+        Self::write_list_by_ref(list, same, buf, tag);
+    }
+
+    #[doc(hidden)]
+    fn list_write_len_ref(list: &[Self]) -> EncodeResult<usize> {
+        Self::list_write_len_by_ref(list, same)
+    }
+
+    /// Like [`JceType::write_list_by`].
+    #[doc(hidden)]
+    fn write_list_by_ref<B: BufMut, T>(
+        list: &[T],
+        get: impl Fn(&T) -> &Self,
+        buf: &mut B,
+        tag: u8,
+    ) {
+        write_header(
+            buf,
+            JceHeader {
+                val_type: LIST,
+                tag,
+            },
+        );
+
+        write_len(buf, list.len());
+
+        for val in list {
+            get(val).write_ref(buf, 0);
+        }
+    }
+
+    #[doc(hidden)]
+    fn list_write_len_by_ref<T>(list: &[T], get: impl Fn(&T) -> &Self) -> EncodeResult<usize> {
+        Ok(len_bytes(list.len())?
+            + 1 // len type
+            + list
+                .iter()
+                .map(|val| Ok(1 + get(val).write_len_ref()?))
+                .sum::<EncodeResult<usize>>()?)
+    }
+}
+
+crate::impl_type_ref!(i8, u8, i16, u16, i32, u32, i64, u64);
+crate::impl_type_ref!(f32, f64, bool, String, bytes::Bytes);
+
+impl<'de, T: JceTypeRef<'de>> JceTypeRef<'de> for Option<T> {
+    fn read_ref(
+        buf: &mut &'de [u8],
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<Self> {
+        if t == ZERO_TAG {
+            return Ok(T::read_ref(buf, t, struct_name, field).ok());
+        }
+
+        Ok(Some(T::read_ref(buf, t, struct_name, field)?))
+    }
+
+    fn write_ref<B: BufMut>(&self, buf: &mut B, tag: u8) {
+        if let Some(t) = self {
+            t.write_ref(buf, tag);
+        } else {
+            write_empty(buf, tag);
+        }
+    }
+
+    fn write_len_ref(&self) -> EncodeResult<usize> {
+        match self {
+            Some(t) => t.write_len_ref(),
+            None => Ok(0),
+        }
+    }
+
+    fn write_field_ref<B: BufMut>(&self, buf: &mut B, tag: u8) {
+        if let Some(t) = self {
+            t.write_field_ref(buf, tag);
+        }
+    }
+
+    fn field_len_ref(&self, tag: u8) -> EncodeResult<usize> {
+        match self {
+            Some(t) => t.field_len_ref(tag),
+            None => Ok(0),
+        }
+    }
+}
+
+impl<'de, T: JceTypeRef<'de>> JceTypeRef<'de> for Vec<T> {
+    fn read_ref(
+        buf: &mut &'de [u8],
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<Self> {
+        T::read_list_ref(buf, t, struct_name, field)
+    }
+
+    fn write_ref<B: BufMut>(&self, buf: &mut B, tag: u8) {
+        T::write_list_ref(self, buf, tag);
+    }
+
+    fn write_len_ref(&self) -> EncodeResult<usize> {
+        T::list_write_len_ref(self)
+    }
 }
 
 impl<T: JceType> JceType for Option<T> {
@@ -117,13 +398,15 @@ impl<T: JceType> JceType for Option<T> {
         struct_name: &'static str,
         field: &'static str,
     ) -> DecodeResult<Self> {
-        Ok(if t != EMPTY {
-            Some(T::read(buf, t, struct_name, field)?)
-        } else {
-            None
-        })
+        if t == ZERO_TAG {
+            // a numeric zero, or `None` written by older versions
+            return Ok(T::read(buf, t, struct_name, field).ok());
+        }
+
+        Ok(Some(T::read(buf, t, struct_name, field)?))
     }
 
+    // `None` in a list or map can't be left out
     fn write<B: BufMut>(&self, buf: &mut B, tag: u8) {
         if let Some(t) = self {
             t.write(buf, tag);
@@ -132,10 +415,23 @@ impl<T: JceType> JceType for Option<T> {
         }
     }
 
-    fn write_len(&self) -> usize {
+    fn write_len(&self) -> EncodeResult<usize> {
         match self {
             Some(t) => t.write_len(),
-            None => 0,
+            None => Ok(0),
+        }
+    }
+
+    fn write_field<B: BufMut>(&self, buf: &mut B, tag: u8) {
+        if let Some(t) = self {
+            t.write_field(buf, tag);
+        }
+    }
+
+    fn field_len(&self, tag: u8) -> EncodeResult<usize> {
+        match self {
+            Some(t) => t.field_len(tag),
+            None => Ok(0),
         }
     }
 }
@@ -176,64 +472,167 @@ macro_rules! primitive_type {
                     buf.$write(*self);
                 }
 
-                fn write_len(&self) -> usize {
-                    ::std::mem::size_of::<$type>()
+                fn write_len(&self) -> $crate::error::EncodeResult<usize> {
+                    Ok(::std::mem::size_of::<$type>())
                 }
             }
         }
     };
 }
 
-primitive_type! {
+// Integers are written with the smallest type that holds the value.
+// Unsigned values are written as `i64`, so `200u8` becomes SHORT like
+// UInt8 of TarsCpp, and only a `u64` above `i64::MAX` keeps its raw bits.
+macro_rules! integer_type {
+    (
+        $type:ident,
+        $jce_type:ident,
+        $read:ident,
+        $write:ident
+        $(, list = $list:ident)? $(,)?
+    ) => {
+        mod $type {
+            impl $crate::types::JceType for $type {
+                fn read<B: ::bytes::Buf>(
+                    buf: &mut B,
+                    t: u8,
+                    struct_name: &'static str,
+                    field: &'static str,
+                ) -> $crate::error::DecodeResult<Self> {
+                    if t == $crate::types::$jce_type {
+                        // the width of the field, keep the raw bits like older
+                        // versions wrote unsigned values, sign-extend the rest
+                        if ::std::mem::size_of::<$type>() > buf.remaining() {
+                            return ::core::result::Result::Err($crate::error::DecodeError::Eof);
+                        }
+
+                        return Ok(buf.$read());
+                    }
+
+                    let value = $crate::types::read_int(buf, t, struct_name, field)?;
+
+                    <$type>::try_from(value).map_err(|_| $crate::error::DecodeError::OutOfRange {
+                        struct_name,
+                        field,
+                        value,
+                    })
+                }
+
+                fn write<B: ::bytes::BufMut>(&self, buf: &mut B, tag: u8) {
+                    match i64::try_from(*self) {
+                        Ok(val) => $crate::ser::write_int(buf, tag, val),
+                        Err(_) => {
+                            $crate::ser::write_header(
+                                buf,
+                                $crate::types::JceHeader {
+                                    val_type: $crate::types::$jce_type,
+                                    tag,
+                                },
+                            );
+
+                            buf.$write(*self);
+                        }
+                    }
+                }
+
+                fn write_len(&self) -> $crate::error::EncodeResult<usize> {
+                    match i64::try_from(*self) {
+                        Ok(val) => Ok($crate::ser::int_len(val)),
+                        Err(_) => Ok(::std::mem::size_of::<$type>()),
+                    }
+                }
+
+                $(
+                fn read_list<B: ::bytes::Buf>(
+                    buf: &mut B,
+                    t: u8,
+                    struct_name: &'static str,
+                    field: &'static str,
+                ) -> $crate::error::DecodeResult<Vec<Self>> {
+                    $crate::types::$list::read_list(buf, t, struct_name, field)
+                }
+
+                fn write_list<B: ::bytes::BufMut>(list: &[Self], buf: &mut B, tag: u8) {
+                    $crate::types::$list::write_slice(buf, list, tag);
+                }
+
+                fn list_write_len(list: &[Self]) -> $crate::error::EncodeResult<usize> {
+                    $crate::types::$list::slice_encoded_len(list)
+                }
+
+                fn write_list_by<B: ::bytes::BufMut, T>(
+                    list: &[T],
+                    get: impl Fn(&T) -> &Self,
+                    buf: &mut B,
+                    tag: u8,
+                ) {
+                    let list: Vec<Self> = list.iter().map(|val| *get(val)).collect();
+                    Self::write_list(&list, buf, tag);
+                }
+
+                fn list_write_len_by<T>(
+                    list: &[T],
+                    _: impl Fn(&T) -> &Self,
+                ) -> $crate::error::EncodeResult<usize> {
+                    $crate::types::$list::bytes_encoded_len(list.len())
+                }
+                )?
+            }
+        }
+    };
+}
+
+integer_type! {
     i8,
     BYTE,
     get_i8,
     put_i8
 }
 
-primitive_type! {
+integer_type! {
     u8,
     BYTE,
     get_u8,
-    put_u8
+    put_u8,
+    list = byte_array,
 }
 
-primitive_type! {
+integer_type! {
     i16,
     SHORT,
     get_i16,
     put_i16
 }
 
-primitive_type! {
+integer_type! {
     u16,
     SHORT,
     get_u16,
     put_u16
 }
 
-primitive_type! {
+integer_type! {
     i32,
     INT,
     get_i32,
     put_i32
 }
 
-primitive_type! {
+integer_type! {
     u32,
     INT,
     get_u32,
     put_u32
 }
 
-primitive_type! {
+integer_type! {
     i64,
     LONG,
     get_i64,
     put_i64
 }
 
-primitive_type! {
+integer_type! {
     u64,
     LONG,
     get_u64,
@@ -254,108 +653,34 @@ primitive_type! {
     put_f64
 }
 
-macro_rules! type_array {
-    (
-        $(
-        $type:ident,
-        $jce_type:ident,
-        $read:ident,
-        $write:ident $(,)?
-        );* $(;)?
-    ) => {
-        mod arrays {
-            $(
-            impl $crate::types::JceType for Vec<$type> {
-                fn read<B: ::bytes::Buf>(
-                    buf: &mut B,
-                    t: u8,
-                    struct_name: &'static str,
-                    field: &'static str,
-                ) -> $crate::error::DecodeResult<Self> {
-                    $crate::types::check_type(t, $crate::types::LIST, struct_name, field)?;
-
-                    let len = $crate::de::read_len(buf)?;
-                    let mut v = Vec::with_capacity(len);
-
-                    for _ in 0..len {
-                        let t = $crate::types::read_type(buf)?;
-                        v.push(<$type as $crate::types::JceType>::read(buf, t, struct_name, field)?);
-                    }
-
-                    Ok(v)
-                }
-
-                fn write<B: ::bytes::BufMut>(&self, buf: &mut B, tag: u8) {
-                    $crate::ser::write_header(
-                        buf,
-                        $crate::types::JceHeader {
-                            val_type: $crate::types::LIST,
-                            tag,
-                        }
-                    );
-
-                    $crate::ser::write_len(buf, self.len());
+mod list {
+    use crate::error::{DecodeResult, EncodeResult};
+    use crate::types::JceType;
+    use bytes::{Buf, BufMut};
 
-                    for val in self {
-                        <$type as $crate::types::JceType>::write(val, buf, 0);
-                    }
-                }
+    impl<T: JceType> JceType for Vec<T> {
+        fn read<B: Buf>(
+            buf: &mut B,
+            t: u8,
+            struct_name: &'static str,
+            field: &'static str,
+        ) -> DecodeResult<Self> {
+            T::read_list(buf, t, struct_name, field)
+        }
 
-                fn write_len(&self) -> usize {
-                    let len = self.len();
-                    $crate::ser::len_bytes(len)
-                    + len // each header
-                    + len * ::std::mem::size_of::<$type>()
-                    + 1 // len type
-                }
-            }
-            )*
+        fn write<B: BufMut>(&self, buf: &mut B, tag: u8) {
+            T::write_list(self, buf, tag);
         }
-    };
-}
 
-type_array! {
-    i8,
-    BYTE,
-    get_i8,
-    put_i8;
-    i16,
-    SHORT,
-    get_i16,
-    put_i16;
-    u16,
-    SHORT,
-    get_u16,
-    put_u16;
-    i32,
-    INT,
-    get_i32,
-    put_i32;
-    u32,
-    INT,
-    get_u32,
-    put_u32;
-    i64,
-    LONG,
-    get_i64,
-    put_i64;
-    u64,
-    LONG,
-    get_u64,
-    put_u64;
-    f32,
-    FLOAT,
-    get_f32,
-    put_f32;
-    f64,
-    DOUBLE,
-    get_f64,
-    put_f64
+        fn write_len(&self) -> EncodeResult<usize> {
+            T::list_write_len(self)
+        }
+    }
 }
 
 mod bool {
-    use crate::error::DecodeResult;
-    use crate::types::JceType;
+    use crate::error::{DecodeResult, EncodeResult};
+    use crate::types::{read_int, JceType};
     use bytes::{Buf, BufMut};
 
     impl JceType for bool {
@@ -365,27 +690,27 @@ mod bool {
             struct_name: &'static str,
             field: &'static str,
         ) -> DecodeResult<Self> {
-            Ok(<u8 as JceType>::read(buf, t, struct_name, field)? != 0)
+            Ok(read_int(buf, t, struct_name, field)? != 0)
         }
 
         fn write<B: BufMut>(&self, buf: &mut B, tag: u8) {
             (*self as u8).write(buf, tag);
         }
 
-        fn write_len(&self) -> usize {
-            std::mem::size_of::<bool>()
+        fn write_len(&self) -> EncodeResult<usize> {
+            (*self as u8).write_len()
         }
     }
 }
 
 mod byte_array {
-    use crate::de::{check_buf, check_buf_zero, read_len};
-    use crate::error::{DecodeError, DecodeResult};
+    use crate::de::{alloc_bytes, alloc_elements, check_buf, check_buf_zero, read_len};
+    use crate::error::{DecodeError, DecodeResult, EncodeError, EncodeResult};
     use crate::ser::write_header;
     use crate::types::{read_type, JceHeader, JceType};
     use bytes::{Buf, BufMut};
 
-    fn read_bytes_len<B: Buf>(
+    pub(super) fn read_bytes_len<B: Buf>(
         buf: &mut B,
         t: u8,
         struct_name: &'static str,
@@ -431,8 +756,8 @@ mod byte_array {
             return Err(DecodeError::Eof);
         }
 
-        value[..len].copy_from_slice(&buf.chunk()[..len]);
-        buf.advance(len);
+        // the bytes may span several chunks
+        buf.copy_to_slice(&mut value[..len]);
 
         Ok(())
     }
@@ -460,59 +785,57 @@ mod byte_array {
 
             buf.put_u32(len);
         } else {
-            panic!("bytes too long");
+            // `encode` fails on it before writing anything
+            panic!("{}", EncodeError::BytesTooLong(len));
         }
 
         buf.put_slice(value);
     }
 
-    pub fn slice_encoded_len(slice: &[u8]) -> usize {
-        let bytes_len = if slice.len() < u8::MAX as usize {
-            1
-        } else {
-            4
-        };
-
-        bytes_len + slice.len()
+    pub fn slice_encoded_len(slice: &[u8]) -> EncodeResult<usize> {
+        bytes_encoded_len(slice.len())
     }
 
-    impl JceType for Vec<u8> {
-        fn read<B: Buf>(
-            buf: &mut B,
-            t: u8,
-            struct_name: &'static str,
-            field: &'static str,
-        ) -> DecodeResult<Vec<u8>> {
-            if t == super::LIST {
-                let len = read_len(buf)?;
-
-                let mut v = Vec::with_capacity(len);
+    /// Length of bytes of `len` bytes written after the header.
+    pub fn bytes_encoded_len(len: usize) -> EncodeResult<usize> {
+        if u32::try_from(len).is_err() {
+            return Err(EncodeError::BytesTooLong(len));
+        }
 
-                for _ in 0..len {
-                    let t = read_type(buf)?;
-                    super::check_type(t, super::BYTE, struct_name, field)?;
+        let bytes_len = if len <= u8::MAX as usize { 1 } else { 4 };
 
-                    v.push(buf.get_u8());
-                }
+        Ok(bytes_len + len)
+    }
 
-                return Ok(v);
-            }
+    /// Reads a `Vec<u8>`, as bytes or as a LIST of BYTE.
+    pub fn read_list<B: Buf>(
+        buf: &mut B,
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<Vec<u8>> {
+        if t == super::LIST {
+            let len = read_len(buf)?;
+            alloc_elements(len, 1)?;
 
-            let len = read_bytes_len(buf, t, struct_name, field)?;
+            let mut v = Vec::with_capacity(len.min(buf.remaining()));
 
-            let mut v = vec![0u8; len];
-            read_slice(buf, &mut v, len)?;
+            for _ in 0..len {
+                let t = read_type(buf)?;
+                v.push(u8::read(buf, t, struct_name, field)?);
+            }
 
-            Ok(v)
+            return Ok(v);
         }
 
-        fn write<B: BufMut>(&self, buf: &mut B, tag: u8) {
-            write_slice(buf, self, tag);
-        }
+        let len = read_bytes_len(buf, t, struct_name, field)?;
+        check_buf(buf, len)?;
+        alloc_bytes(len)?;
 
-        fn write_len(&self) -> usize {
-            slice_encoded_len(self)
-        }
+        let mut v = vec![0u8; len];
+        read_slice(buf, &mut v, len)?;
+
+        Ok(v)
     }
 
     impl JceType for bytes::Bytes {
@@ -522,18 +845,31 @@ mod byte_array {
             struct_name: &'static str,
             field: &'static str,
         ) -> DecodeResult<Self> {
-            <Vec<u8> as JceType>::read(buf, t, struct_name, field).map(bytes::Bytes::from)
+            if t == super::LIST {
+                return read_list(buf, t, struct_name, field).map(bytes::Bytes::from);
+            }
+
+            let len = read_bytes_len(buf, t, struct_name, field)?;
+            check_buf(buf, len)?;
+            alloc_bytes(len)?;
+
+            // a cheap slice if the input is `Bytes` or `BytesMut`
+            Ok(buf.copy_to_bytes(len))
         }
 
         fn write<B: BufMut>(&self, buf: &mut B, tag: u8) {
             write_slice(buf, self, tag);
         }
 
-        fn write_len(&self) -> usize {
+        fn write_len(&self) -> EncodeResult<usize> {
             slice_encoded_len(self)
         }
     }
 
+    impl<'de, const N: usize> crate::types::JceTypeRef<'de> for [u8; N] {
+        crate::__type_ref_methods!('de);
+    }
+
     impl<const N: usize> JceType for [u8; N] {
         fn read<B: Buf>(
             buf: &mut B,
@@ -557,14 +893,107 @@ mod byte_array {
             write_slice(buf, self, tag);
         }
 
-        fn write_len(&self) -> usize {
+        fn write_len(&self) -> EncodeResult<usize> {
             slice_encoded_len(self)
         }
     }
 }
 
+mod borrowed {
+    use super::byte_array::{read_bytes_len, slice_encoded_len, write_slice};
+    use crate::de::{check_buf, check_bytes_len};
+    use crate::error::{DecodeResult, EncodeResult};
+    use crate::types::JceTypeRef;
+    use bytes::BufMut;
+    use std::borrow::Cow;
+
+    impl<'de: 'a, 'a> JceTypeRef<'de> for &'a [u8] {
+        fn read_ref(
+            buf: &mut &'de [u8],
+            t: u8,
+            struct_name: &'static str,
+            field: &'static str,
+        ) -> DecodeResult<Self> {
+            let len = read_bytes_len(buf, t, struct_name, field)?;
+            check_buf(buf, len)?;
+            check_bytes_len(len)?;
+
+            let (bytes, rest) = buf.split_at(len);
+            *buf = rest;
+
+            Ok(bytes)
+        }
+
+        fn write_ref<B: BufMut>(&self, buf: &mut B, tag: u8) {
+            write_slice(buf, self, tag);
+        }
+
+        fn write_len_ref(&self) -> EncodeResult<usize> {
+            slice_encoded_len(self)
+        }
+    }
+
+    impl<'de: 'a, 'a> JceTypeRef<'de> for &'a str {
+        fn read_ref(
+            buf: &mut &'de [u8],
+            t: u8,
+            struct_name: &'static str,
+            field: &'static str,
+        ) -> DecodeResult<Self> {
+            let bytes = <&[u8]>::read_ref(buf, t, struct_name, field)?;
+            Ok(std::str::from_utf8(bytes)?)
+        }
+
+        fn write_ref<B: BufMut>(&self, buf: &mut B, tag: u8) {
+            write_slice(buf, self.as_bytes(), tag);
+        }
+
+        fn write_len_ref(&self) -> EncodeResult<usize> {
+            slice_encoded_len(self.as_bytes())
+        }
+    }
+
+    impl<'de: 'a, 'a> JceTypeRef<'de> for Cow<'a, [u8]> {
+        fn read_ref(
+            buf: &mut &'de [u8],
+            t: u8,
+            struct_name: &'static str,
+            field: &'static str,
+        ) -> DecodeResult<Self> {
+            <&[u8]>::read_ref(buf, t, struct_name, field).map(Cow::Borrowed)
+        }
+
+        fn write_ref<B: BufMut>(&self, buf: &mut B, tag: u8) {
+            write_slice(buf, self, tag);
+        }
+
+        fn write_len_ref(&self) -> EncodeResult<usize> {
+            slice_encoded_len(self)
+        }
+    }
+
+    impl<'de: 'a, 'a> JceTypeRef<'de> for Cow<'a, str> {
+        fn read_ref(
+            buf: &mut &'de [u8],
+            t: u8,
+            struct_name: &'static str,
+            field: &'static str,
+        ) -> DecodeResult<Self> {
+            <&str>::read_ref(buf, t, struct_name, field).map(Cow::Borrowed)
+        }
+
+        fn write_ref<B: BufMut>(&self, buf: &mut B, tag: u8) {
+            write_slice(buf, self.as_bytes(), tag);
+        }
+
+        fn write_len_ref(&self) -> EncodeResult<usize> {
+            slice_encoded_len(self.as_bytes())
+        }
+    }
+}
+
 mod string {
-    use crate::error::DecodeResult;
+    use crate::error::{DecodeResult, EncodeResult};
     use crate::types::JceType;
     use bytes::{Buf, BufMut};
 
@@ -583,15 +1012,15 @@ mod string {
             super::byte_array::write_slice(buf, self.as_bytes(), tag);
         }
 
-        fn write_len(&self) -> usize {
+        fn write_len(&self) -> EncodeResult<usize> {
             super::byte_array::slice_encoded_len(self.as_bytes())
         }
     }
 }
 
 mod map {
-    use crate::de::read_len;
-    use crate::error::DecodeResult;
+    use crate::de::{alloc_elements, enter, read_len};
+    use crate::error::{DecodeResult, EncodeResult};
     use crate::ser::{write_header, write_len};
     use crate::types::{read_type, JceHeader, JceType};
     use bytes::{Buf, BufMut};
@@ -601,6 +1030,15 @@ mod map {
     const TAG_KEY: u8 = 0;
     const TAG_VAL: u8 = 1;
 
+    impl<'de, K, V> crate::types::JceTypeRef<'de> for HashMap<K, V>
+    where
+        K: JceType,
+        K: Eq + Hash,
+        V: JceType,
+    {
+        crate::__type_ref_methods!('de);
+    }
+
     impl<K, V> JceType for HashMap<K, V>
     where
         K: JceType,
@@ -615,7 +1053,9 @@ mod map {
         ) -> DecodeResult<Self> {
             super::check_type(t, super::MAP, struct_name, field)?;
 
+            let _guard = enter()?;
             let len = read_len(buf)?;
+            alloc_elements(len, std::mem::size_of::<(K, V)>())?;
 
             let mut map = Self::new();
 
@@ -649,23 +1089,23 @@ mod map {
             }
         }
 
-        fn write_len(&self) -> usize {
-            if let Some((k, v)) = self.iter().next() {
-                let len = self.len();
-
-                (len * 2) + (len * (k.write_len() + v.write_len()))
-            } else {
-                0
-            }
+        fn write_len(&self) -> EncodeResult<usize> {
+            Ok(crate::ser::len_bytes(self.len())?
+                + 1 // len type
+                + self
+                    .iter()
+                    .map(|(k, v)| Ok(2 + k.write_len()? + v.write_len()?))
+                    .sum::<EncodeResult<usize>>()?)
         }
     }
 }
 
 mod jce_struct {
-    use crate::error::DecodeResult;
+    use crate::de::enter;
+    use crate::error::{DecodeResult, EncodeResult};
     use crate::ser::{write_header, write_type};
     use crate::types::{JceHeader, JceType};
-    use crate::JceStruct;
+    use crate::{JceStruct, JceStructRef};
     use bytes::{Buf, BufMut};
 
     impl<T: JceStruct> JceType for T {
@@ -677,6 +1117,7 @@ mod jce_struct {
         ) -> DecodeResult<Self> {
             super::check_type(t, super::STRUCT_START, struct_name, field)?;
 
+            let _guard = enter()?;
             Self::decode_raw(buf, false)
         }
 
@@ -692,18 +1133,121 @@ mod jce_struct {
             write_type(buf, super::STRUCT_END);
         }
 
-        fn write_len(&self) -> usize {
-            <Self as JceStruct>::encoded_len(self) + 1
+        fn write_len(&self) -> EncodeResult<usize> {
+            Ok(<Self as JceStruct>::encoded_len(self)? + 1)
         }
     }
+
+    impl<'de, T: JceStruct> crate::types::JceTypeRef<'de> for T {
+        crate::__type_ref_methods!('de);
+    }
+
+    // `JceTypeRef` of derived borrowed structs
+
+    #[doc(hidden)]
+    pub fn read_struct_ref<'de, T: JceStructRef<'de>>(
+        buf: &mut &'de [u8],
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<T> {
+        super::check_type(t, super::STRUCT_START, struct_name, field)?;
+
+        let _guard = enter()?;
+        T::decode_raw_ref(buf, false)
+    }
+
+    #[doc(hidden)]
+    pub fn write_struct_ref<'de, T: JceStructRef<'de>, B: BufMut>(val: &T, buf: &mut B, tag: u8) {
+        write_header(
+            buf,
+            JceHeader {
+                val_type: super::STRUCT_START,
+                tag,
+            },
+        );
+        val.encode_raw(buf);
+        write_type(buf, super::STRUCT_END);
+    }
 }
 
-fn read_type<B: Buf>(buf: &mut B) -> DecodeResult<u8> {
+#[doc(hidden)]
+pub use crate::ser::header_len;
+#[doc(hidden)]
+pub use crate::unknown::capture_field;
+
+/// A derived struct, which knows the tags of its fields to be flattened.
+#[doc(hidden)]
+pub trait Flatten: crate::JceStruct {
+    /// Tags of the fields, including flattened ones.
+    const TAGS: Tags;
+}
+
+/// A set of tags, to find the fields of a flattened struct.
+#[doc(hidden)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Tags([u64; 4]);
+
+impl Tags {
+    pub const EMPTY: Self = Self([0; 4]);
+
+    pub const fn new(tags: &[u8]) -> Self {
+        let mut set = [0; 4];
+
+        let mut i = 0;
+        while i < tags.len() {
+            set[(tags[i] >> 6) as usize] |= 1 << (tags[i] & 63);
+            i += 1;
+        }
+
+        Self(set)
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        let mut set = self.0;
+
+        let mut i = 0;
+        while i < 4 {
+            set[i] |= other.0[i];
+            i += 1;
+        }
+
+        Self(set)
+    }
+
+    pub const fn overlaps(self, other: Self) -> bool {
+        let mut i = 0;
+        while i < 4 {
+            if self.0[i] & other.0[i] != 0 {
+                return true;
+            }
+            i += 1;
+        }
+
+        false
+    }
+
+    #[inline]
+    pub const fn contains(self, tag: u8) -> bool {
+        self.0[(tag >> 6) as usize] & (1 << (tag & 63)) != 0
+    }
+}
+#[doc(hidden)]
+pub use jce_struct::{read_struct_ref, write_struct_ref};
+
+pub(crate) fn read_type<B: Buf>(buf: &mut B) -> DecodeResult<u8> {
     check_buf_zero(buf)?;
     Ok(buf.get_u8() & 0xF)
 }
 
 pub fn skip_field<B: Buf>(buf: &mut B, t: u8) -> DecodeResult<()> {
+    fn skip<B: Buf>(buf: &mut B, len: usize) -> DecodeResult<()> {
+        check_buf(buf, len)?;
+        buf.advance(len);
+
+        Ok(())
+    }
+
     fn skip_elem<B: Buf>(buf: &mut B) -> DecodeResult<()> {
         let t = read_type(buf)?;
 
@@ -711,33 +1255,46 @@ pub fn skip_field<B: Buf>(buf: &mut B, t: u8) -> DecodeResult<()> {
     }
 
     match t {
-        BYTE => buf.advance(1),
-        SHORT => buf.advance(2),
-        INT | FLOAT => buf.advance(4),
-        LONG | DOUBLE => buf.advance(8),
+        BYTE => skip(buf, 1)?,
+        SHORT => skip(buf, 2)?,
+        INT | FLOAT => skip(buf, 4)?,
+        LONG | DOUBLE => skip(buf, 8)?,
         SHORT_BYTES => {
+            check_buf_zero(buf)?;
             let len = buf.get_u8() as usize;
-            check_buf(buf, len)?;
-            buf.advance(len);
+            skip(buf, len)?;
         }
         LONG_BYTES => {
+            check_buf(buf, 4)?;
             let len = buf.get_u32() as usize;
-            check_buf(buf, len)?;
-            buf.advance(len);
+            skip(buf, len)?;
         }
         STRUCT_START => {
-            skip_elem(buf)?;
+            let _guard = enter()?;
+
+            loop {
+                // skip fields until the matching STRUCT_END
+                let header = read_header(buf)?;
+                if header.val_type == STRUCT_END {
+                    break;
+                }
+
+                skip_field(buf, header.val_type)?;
+            }
         }
         STRUCT_END | EMPTY => {}
         MAP => {
+            let _guard = enter()?;
             let len = read_len(buf)?;
 
-            for _ in 0..len * 2 {
+            for _ in 0..len {
                 // skip key and value
                 skip_elem(buf)?;
+                skip_elem(buf)?;
             }
         }
         LIST => {
+            let _guard = enter()?;
             let len = read_len(buf)?;
 
             for _ in 0..len {
@@ -757,7 +1314,8 @@ pub fn skip_field<B: Buf>(buf: &mut B, t: u8) -> DecodeResult<()> {
                 _ => 1,
             };
 
-            buf.advance(len * single);
+            let len = len.checked_mul(single).ok_or(DecodeError::InvalidLength)?;
+            skip(buf, len)?;
         }
         _ => return Err(DecodeError::InvalidType),
     }
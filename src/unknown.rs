@@ -0,0 +1,186 @@
+use crate::de::alloc;
+use crate::error::{DecodeResult, EncodeError, EncodeResult};
+use crate::ser::{header_len, write_header};
+use crate::types::{skip_field, JceHeader, Tags};
+use bytes::{Buf, BufMut, Bytes};
+
+/// A field whose tag isn't known to the struct, kept as raw bytes.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UnknownField {
+    tag: u8,
+    value_type: u8,
+    value: Bytes,
+}
+
+impl UnknownField {
+    #[inline]
+    pub fn tag(&self) -> u8 {
+        self.tag
+    }
+
+    #[inline]
+    pub fn value_type(&self) -> u8 {
+        self.value_type
+    }
+
+    /// The encoded value, without the header.
+    #[inline]
+    pub fn value(&self) -> &Bytes {
+        &self.value
+    }
+
+    fn encoded_len(&self) -> usize {
+        header_len(self.tag) + self.value.len()
+    }
+
+    fn write<B: BufMut>(&self, buf: &mut B) {
+        write_header(
+            buf,
+            JceHeader {
+                val_type: self.value_type,
+                tag: self.tag,
+            },
+        );
+        buf.put_slice(&self.value);
+    }
+}
+
+/// Fields of a newer schema, kept by a `#[jce(unknown_fields)]` field
+/// and written back in tag order.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct UnknownFields {
+    // sorted by tag
+    fields: Vec<UnknownField>,
+}
+
+impl UnknownFields {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, UnknownField> {
+        self.fields.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.fields.clear();
+    }
+
+    pub fn encoded_len(&self) -> usize {
+        self.fields.iter().map(UnknownField::encoded_len).sum()
+    }
+
+    /// The encoded length, failing if a field has one of `tags`.
+    #[doc(hidden)]
+    pub fn checked_len(&self, tags: Tags) -> EncodeResult<usize> {
+        if let Some(field) = self.fields.iter().find(|f| tags.contains(f.tag)) {
+            return Err(EncodeError::InvalidTag(field.tag));
+        }
+
+        Ok(self.encoded_len())
+    }
+
+    /// Reads the value of an unknown field after its header.
+    #[doc(hidden)]
+    pub fn read_field<B: Buf>(&mut self, buf: &mut B, tag: u8, t: u8) -> DecodeResult<()> {
+        let mut value = Vec::new();
+        skip_field(
+            &mut Recorder {
+                buf,
+                out: &mut value,
+            },
+            t,
+        )?;
+        alloc(value.len())?;
+
+        let at = self.fields.partition_point(|f| f.tag <= tag);
+        self.fields.insert(
+            at,
+            UnknownField {
+                tag,
+                value_type: t,
+                value: value.into(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Writes the fields from `*from` with a tag lower than `tag`.
+    #[doc(hidden)]
+    pub fn write_before<B: BufMut>(&self, buf: &mut B, from: &mut usize, tag: u8) {
+        while let Some(field) = self.fields.get(*from).filter(|f| f.tag < tag) {
+            field.write(buf);
+            *from += 1;
+        }
+    }
+
+    /// Writes the fields from `from` to the end.
+    #[doc(hidden)]
+    pub fn write_rest<B: BufMut>(&self, buf: &mut B, from: usize) {
+        for field in self.fields.iter().skip(from) {
+            field.write(buf);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a UnknownFields {
+    type Item = &'a UnknownField;
+    type IntoIter = std::slice::Iter<'a, UnknownField>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Copies a field, header included, to the end of `out`.
+#[doc(hidden)]
+pub fn capture_field<B: Buf>(buf: &mut B, tag: u8, t: u8, out: &mut Vec<u8>) -> DecodeResult<()> {
+    let start = out.len();
+
+    write_header(out, JceHeader { val_type: t, tag });
+    skip_field(&mut Recorder { buf, out }, t)?;
+
+    alloc(out.len() - start)
+}
+
+/// Copies everything read from `buf`.
+struct Recorder<'a, B> {
+    buf: &'a mut B,
+    out: &'a mut Vec<u8>,
+}
+
+impl<B: Buf> Buf for Recorder<'_, B> {
+    fn remaining(&self) -> usize {
+        self.buf.remaining()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.buf.chunk()
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        while cnt > 0 {
+            let chunk = self.buf.chunk();
+            let n = chunk.len().min(cnt);
+            if n == 0 {
+                break;
+            }
+
+            self.out.extend_from_slice(&chunk[..n]);
+            self.buf.advance(n);
+            cnt -= n;
+        }
+
+        // past the end, let `buf` panic
+        self.buf.advance(cnt);
+    }
+}
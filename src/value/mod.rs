@@ -1,4 +1,8 @@
-use crate::de::{read_header, read_len};
+use crate::check_type;
+use crate::de::{
+    alloc_bytes, alloc_elements, check_buf, check_buf_zero, enter, read_header, read_len,
+    with_limits, DecodeLimits,
+};
 use crate::error::{DecodeError, DecodeResult};
 use crate::types;
 use bytes::{Buf, Bytes};
@@ -86,35 +90,55 @@ impl Hash for Value {
 }
 
 fn read_value<B: Buf>(buf: &mut B, t: u8) -> DecodeResult<Value> {
-    fn bytes_from_buf<B: Buf>(buf: &mut B, len: usize) -> Bytes {
-        if len > 0 {
-            let b = Bytes::from(buf.chunk()[..len].to_vec());
-            buf.advance(len);
-            b
-        } else {
-            Bytes::new()
-        }
+    fn bytes_from_buf<B: Buf>(buf: &mut B, len: usize) -> DecodeResult<Bytes> {
+        check_buf(buf, len)?;
+        alloc_bytes(len)?;
+
+        // a cheap slice if the input is `Bytes` or `BytesMut`
+        Ok(buf.copy_to_bytes(len))
     }
 
     let val = match t {
-        types::BYTE => Value::Byte(buf.get_i8()),
-        types::SHORT => Value::Short(buf.get_i16()),
-        types::INT => Value::Int(buf.get_i32()),
-        types::LONG => Value::Long(buf.get_i64()),
-        types::FLOAT => Value::Float(buf.get_f32()),
-        types::DOUBLE => Value::Double(buf.get_f64()),
+        types::BYTE => Value::Byte({
+            check_type!(i8, buf);
+            buf.get_i8()
+        }),
+        types::SHORT => Value::Short({
+            check_type!(i16, buf);
+            buf.get_i16()
+        }),
+        types::INT => Value::Int({
+            check_type!(i32, buf);
+            buf.get_i32()
+        }),
+        types::LONG => Value::Long({
+            check_type!(i64, buf);
+            buf.get_i64()
+        }),
+        types::FLOAT => Value::Float({
+            check_type!(f32, buf);
+            buf.get_f32()
+        }),
+        types::DOUBLE => Value::Double({
+            check_type!(f64, buf);
+            buf.get_f64()
+        }),
         types::SHORT_BYTES => Value::Bytes({
+            check_type!(u8, buf);
             let len = buf.get_u8() as usize;
 
-            bytes_from_buf(buf, len)
+            bytes_from_buf(buf, len)?
         }),
         types::LONG_BYTES => Value::Bytes({
+            check_type!(u32, buf);
             let len = buf.get_u32() as usize;
 
-            bytes_from_buf(buf, len)
+            bytes_from_buf(buf, len)?
         }),
         types::MAP => Value::Map({
+            let _guard = enter()?;
             let len = read_len(buf)?;
+            alloc_elements(len, std::mem::size_of::<(String, Value)>())?;
 
             let mut map = HashMap::new();
 
@@ -136,9 +160,11 @@ fn read_value<B: Buf>(buf: &mut B, t: u8) -> DecodeResult<Value> {
             map
         }),
         types::LIST => Value::List({
+            let _guard = enter()?;
             let len = read_len(buf)?;
+            alloc_elements(len, std::mem::size_of::<Value>())?;
 
-            let mut list = Vec::with_capacity(len);
+            let mut list = Vec::with_capacity(len.min(buf.remaining()));
 
             for _ in 0..len {
                 list.push(read_elem(buf)?);
@@ -154,21 +180,32 @@ fn read_value<B: Buf>(buf: &mut B, t: u8) -> DecodeResult<Value> {
 }
 
 pub fn read_elem<B: Buf>(buf: &mut B) -> DecodeResult<Value> {
+    check_buf_zero(buf)?;
+
     let t = buf.get_u8() & 0xF;
     read_value(buf, t)
 }
 
-pub fn read_to_hashmap<B: Buf>(mut buf: B) -> DecodeResult<HashMap<u8, Value>> {
-    let mut map = HashMap::new();
+pub fn read_to_hashmap<B: Buf>(buf: B) -> DecodeResult<HashMap<u8, Value>> {
+    read_to_hashmap_with_limits(buf, DecodeLimits::default())
+}
+
+pub fn read_to_hashmap_with_limits<B: Buf>(
+    mut buf: B,
+    limits: DecodeLimits,
+) -> DecodeResult<HashMap<u8, Value>> {
+    with_limits(limits, || {
+        let mut map = HashMap::new();
 
-    while buf.remaining() > 0 {
-        let header = read_header(&mut buf)?;
-        let value = read_value(&mut buf, header.value_type())?;
+        while buf.remaining() > 0 {
+            let header = read_header(&mut buf)?;
+            let value = read_value(&mut buf, header.value_type())?;
 
-        map.insert(header.tag(), value);
-    }
+            map.insert(header.tag(), value);
+        }
 
-    Ok(map)
+        Ok(map)
+    })
 }
 
 #[cfg(test)]
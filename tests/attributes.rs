@@ -0,0 +1,58 @@
+use jce::JceStruct;
+
+#[derive(JceStruct, PartialEq, Debug)]
+#[jce(default, omit_defaults)]
+struct Attrs {
+    #[jce(tag = 1)]
+    a: i32,
+    // tag = 2
+    b: String,
+    #[jce(tag = 10, required)]
+    c: i64,
+    #[jce(tag = "20", default = 3)]
+    d: u8,
+    #[jce(default = -1)]
+    e: i16,
+    #[jce(skip = Vec::new())]
+    f: Vec<i32>,
+}
+
+#[test]
+fn integer_tags() {
+    let attrs = Attrs {
+        a: 1,
+        b: "下北泽".into(),
+        c: 114514,
+        d: 4,
+        e: 0,
+        f: vec![1],
+    };
+
+    let mut b = vec![];
+    attrs.encode(&mut b).unwrap();
+
+    let tags: Vec<u8> = {
+        let mut buf = &*b;
+        let mut tags = vec![];
+        while !buf.is_empty() {
+            let header = jce::de::read_header(&mut buf).unwrap();
+            jce::types::skip_field(&mut buf, header.value_type()).unwrap();
+            tags.push(header.tag());
+        }
+        tags
+    };
+    assert_eq!(tags, [1, 2, 10, 20, 21]);
+
+    assert_eq!(Attrs::decode(&*b).unwrap(), Attrs { f: vec![], ..attrs });
+    assert_eq!(
+        Attrs::default(),
+        Attrs {
+            a: 0,
+            b: "".into(),
+            c: 0,
+            d: 3,
+            e: -1,
+            f: vec![],
+        }
+    );
+}
@@ -0,0 +1,171 @@
+use jce::{JceStruct, JceStructRef};
+use std::borrow::Cow;
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Msg<'a> {
+    id: i32,
+    name: &'a str,
+    data: &'a [u8],
+    note: Cow<'a, str>,
+    inner: Inner<'a>,
+    owned: Vec<String>,
+    #[jce(tag = "20")]
+    home: Home,
+}
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+struct Inner<'a> {
+    s: &'a str,
+}
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+struct Home {
+    location: String,
+}
+
+#[test]
+fn borrowed() {
+    let msg = Msg {
+        id: 114514,
+        name: "Jack",
+        data: &[1, 9, 1, 9],
+        note: Cow::Owned("810".into()),
+        inner: Inner { s: "下北泽" },
+        owned: vec!["114".into(), "514".into()],
+        home: Home {
+            location: "下北泽".into(),
+        },
+    };
+
+    let mut b = vec![];
+    msg.encode(&mut b).unwrap();
+    assert_eq!(msg.encoded_len(), Ok(b.len()));
+
+    let decoded = Msg::decode_ref(&b).unwrap();
+    assert_eq!(decoded, msg);
+
+    // the fields point into the input
+    let range = b.as_ptr_range();
+    assert!(range.contains(&decoded.name.as_ptr()));
+    assert!(range.contains(&decoded.data.as_ptr()));
+    assert!(matches!(decoded.note, Cow::Borrowed(_)));
+    assert!(range.contains(&decoded.inner.s.as_ptr()));
+}
+
+#[test]
+fn same_wire_format() {
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Owned {
+        id: i32,
+        name: String,
+        data: Vec<u8>,
+    }
+
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Borrowed<'a> {
+        id: i32,
+        name: &'a str,
+        data: &'a [u8],
+    }
+
+    let owned = Owned {
+        id: 1,
+        name: "name".into(),
+        data: vec![0; 300],
+    };
+
+    let mut b = vec![];
+    owned.encode(&mut b).unwrap();
+
+    let borrowed = Borrowed::decode_ref(&b).unwrap();
+    assert_eq!(borrowed.name, owned.name);
+    assert_eq!(borrowed.data, owned.data);
+
+    let mut b2 = vec![];
+    borrowed.encode(&mut b2).unwrap();
+    assert_eq!(b, b2);
+}
+
+#[derive(jce::JceType, PartialEq, Debug)]
+enum Kind {
+    A = 1,
+    B = 2,
+}
+
+#[derive(PartialEq, Debug)]
+struct Uin(i64);
+
+impl jce::types::JceType for Uin {
+    fn read<B: jce::bytes::Buf>(
+        buf: &mut B,
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> jce::error::DecodeResult<Self> {
+        i64::read(buf, t, struct_name, field).map(Self)
+    }
+
+    fn write<B: jce::bytes::BufMut>(&self, buf: &mut B, tag: u8) {
+        self.0.write(buf, tag);
+    }
+
+    fn write_len(&self) -> jce::error::EncodeResult<usize> {
+        self.0.write_len()
+    }
+}
+
+jce::impl_type_ref!(Uin);
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Optional<'a> {
+    name: Option<&'a str>,
+    missing: Option<&'a str>,
+    chunks: Vec<&'a [u8]>,
+    bytes: Vec<u8>,
+    nested: Vec<Option<Cow<'a, str>>>,
+    #[jce(required)]
+    kind: Kind,
+    uin: Option<Uin>,
+}
+
+#[test]
+fn option_and_vec() {
+    let optional = Optional {
+        name: Some("下北泽"),
+        missing: None,
+        chunks: vec![&[1, 1, 4], &[5, 1, 4]],
+        bytes: vec![1, 9, 1, 9],
+        nested: vec![Some(Cow::Borrowed("810")), None],
+        kind: Kind::B,
+        uin: Some(Uin(114514)),
+    };
+
+    let mut b = vec![];
+    optional.encode(&mut b).unwrap();
+    assert_eq!(optional.encoded_len(), Ok(b.len()));
+
+    let decoded = Optional::decode_ref(&b).unwrap();
+    assert_eq!(decoded, optional);
+    assert!(b.as_ptr_range().contains(&decoded.chunks[1].as_ptr()));
+
+    // written like the owned struct, `None` left out and `Vec<u8>` as bytes
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Owned {
+        name: Option<String>,
+        missing: Option<String>,
+        chunks: Vec<Vec<u8>>,
+        bytes: Vec<u8>,
+        nested: Vec<Option<String>>,
+        #[jce(required)]
+        kind: Kind,
+        uin: Option<i64>,
+    }
+
+    let owned = Owned::decode(&*b).unwrap();
+    assert_eq!(owned.missing, None);
+    assert_eq!(owned.nested, [Some("810".into()), None]);
+
+    let mut o = vec![];
+    owned.encode(&mut o).unwrap();
+    assert_eq!(o, b);
+}
@@ -0,0 +1,115 @@
+use bytes::{Buf, Bytes, BytesMut};
+use jce::JceStruct;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Packet {
+    a: i8,
+    b: i16,
+    c: i32,
+    d: i64,
+    e: f32,
+    f: f64,
+    g: String,
+    h: Bytes,
+    i: Vec<u8>,
+    j: [u8; 4],
+    k: HashMap<String, Vec<i32>>,
+    l: Vec<Inner>,
+    #[jce(tag = "20")]
+    m: Inner,
+}
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+struct Inner {
+    s: String,
+    v: u64,
+}
+
+fn packet() -> Packet {
+    Packet {
+        a: -1,
+        b: 300,
+        c: 70000,
+        d: 1 << 40,
+        e: 1.5,
+        f: -2.5,
+        g: "下北泽".into(),
+        h: Bytes::from_static(&[1, 2, 3]),
+        i: vec![114; 300],
+        j: [1, 1, 4, 5],
+        k: HashMap::from([("114".into(), vec![5, 1, 4])]),
+        l: vec![Inner {
+            s: "1919".into(),
+            v: 810,
+        }],
+        m: Inner {
+            s: "".into(),
+            v: u64::MAX,
+        },
+    }
+}
+
+#[test]
+fn split_everywhere() {
+    let p = packet();
+
+    let mut b = vec![];
+    p.encode(&mut b).unwrap();
+
+    for i in 0..=b.len() {
+        let (front, back) = b.split_at(i);
+
+        assert_eq!(Packet::decode(front.chain(back)).unwrap(), p);
+
+        let mut chain = front.chain(back);
+        while chain.has_remaining() {
+            let header = jce::de::read_header(&mut chain).unwrap();
+            jce::types::skip_field(&mut chain, header.value_type()).unwrap();
+        }
+
+        // a ring buffer that wrapped around
+        let mut deque = VecDeque::with_capacity(b.len());
+        deque.extend(vec![0; i]);
+        deque.drain(..);
+        deque.extend(&b);
+        assert_eq!(Packet::decode(deque).unwrap(), p);
+
+        // split network reads
+        let mut bytes = BytesMut::from(&*b);
+        let back = bytes.split_off(i);
+        assert_eq!(Packet::decode(bytes.freeze().chain(back)).unwrap(), p);
+    }
+}
+
+#[test]
+fn split_value() {
+    #[derive(JceStruct)]
+    struct Flat {
+        a: i64,
+        b: String,
+        c: HashMap<String, Vec<u8>>,
+        d: Vec<String>,
+    }
+
+    let mut b = vec![];
+    Flat {
+        a: 1 << 40,
+        b: "下北泽".into(),
+        c: HashMap::from([("114".into(), vec![5, 1, 4])]),
+        d: vec!["1919".into(), "810".into()],
+    }
+    .encode(&mut b)
+    .unwrap();
+
+    let value = jce::value::read_to_hashmap(&*b).unwrap();
+
+    for i in 0..=b.len() {
+        let (front, back) = b.split_at(i);
+
+        assert_eq!(
+            jce::value::read_to_hashmap(front.chain(back)).unwrap(),
+            value
+        );
+    }
+}
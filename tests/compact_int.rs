@@ -0,0 +1,177 @@
+use jce::JceStruct;
+
+#[derive(JceStruct)]
+struct Ints {
+    zero: i32,
+    byte: i32,
+    short: i64,
+    int: i64,
+    long: i64,
+    raw_u8: u8,
+    raw_u32: u32,
+    raw_u16: u16,
+    empty: Vec<i32>,
+    list: Vec<i32>,
+    raw_u64: u64,
+}
+
+#[test]
+fn compact_int() {
+    let ints = Ints {
+        zero: 0,
+        byte: 1,
+        short: 300,
+        int: 70000,
+        long: 1 << 40,
+        raw_u8: 200,
+        raw_u32: u32::MAX,
+        raw_u16: u16::MAX,
+        empty: vec![],
+        list: vec![0, 1000],
+        raw_u64: u64::MAX,
+    };
+
+    let mut b = vec![0u8; 0];
+    ints.encode(&mut b).unwrap();
+
+    assert_eq!(
+        b,
+        [
+            0x0C, // zero: ZERO_TAG
+            0x10, 1, // byte: BYTE
+            0x21, 0x01, 0x2C, // short: SHORT
+            0x32, 0x00, 0x01, 0x11, 0x70, // int: INT
+            0x43, 0, 0, 1, 0, 0, 0, 0, 0, // long: LONG
+            0x51, 0x00, 200, // raw_u8: SHORT
+            0x63, 0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, // raw_u32: LONG
+            0x72, 0, 0, 0xFF, 0xFF, // raw_u16: INT
+            0x89, 0x0C, // empty: LIST, len ZERO_TAG
+            0x99, 0x00, 2, 0x0C, 0x01, 0x03, 0xE8, // list: LIST, len BYTE
+            0xA3, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // raw_u64: LONG, raw bits
+        ]
+    );
+    assert_eq!(ints.encoded_len(), Ok(b.len()));
+
+    // wider signed fields read the same values
+    #[derive(JceStruct, Debug)]
+    struct Signed {
+        #[jce(tag = 5)]
+        raw_u8: i16,
+        raw_u32: i64,
+        raw_u16: i32,
+    }
+
+    let signed = Signed::decode(&*b).unwrap();
+    assert_eq!(signed.raw_u8, 200);
+    assert_eq!(signed.raw_u32, u32::MAX as i64);
+    assert_eq!(signed.raw_u16, u16::MAX as i32);
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Widen {
+    a: i32,
+    b: i64,
+    c: u16,
+    d: bool,
+    e: u64,
+}
+
+#[test]
+fn widening() {
+    // a: ZERO_TAG, b: BYTE -1, c: BYTE 100, d: INT 1, e: SHORT 300
+    let w = Widen::decode(
+        [
+            0x0C, 0x10, 0xFF, 0x20, 100, 0x32, 0, 0, 0, 1, 0x41, 0x01, 0x2C,
+        ]
+        .as_ref(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        w,
+        Widen {
+            a: 0,
+            b: -1,
+            c: 100,
+            d: true,
+            e: 300,
+        }
+    );
+
+    let mut b = vec![0u8; 0];
+    w.encode(&mut b).unwrap();
+    assert_eq!(Widen::decode(&*b).unwrap(), w);
+}
+
+#[test]
+fn narrowing() {
+    #[derive(JceStruct, Debug)]
+    struct Narrow {
+        a: i8,
+    }
+
+    // fits
+    assert_eq!(
+        Narrow::decode([0x02, 0, 0, 0, 127].as_ref()).unwrap().a,
+        127
+    );
+
+    // doesn't fit
+    assert!(matches!(
+        Narrow::decode([0x02, 0, 0, 0, 128].as_ref()),
+        Err(jce::error::DecodeError::OutOfRange { value: 128, .. })
+    ));
+
+    // negative into unsigned
+    #[derive(JceStruct, Debug)]
+    struct Unsigned {
+        a: u32,
+    }
+
+    assert!(matches!(
+        Unsigned::decode([0x00, 0xFF].as_ref()),
+        Err(jce::error::DecodeError::OutOfRange { value: -1, .. })
+    ));
+}
+
+#[test]
+fn raw_bits() {
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Unsigned {
+        a: u8,
+        b: u16,
+        c: u32,
+        d: u64,
+    }
+
+    // written by older versions in the width of the field: BYTE 0xFF,
+    // SHORT 0xFFFF, INT 0xFFFFFFFF, LONG 0xFFFFFFFFFFFFFFFF
+    let mut b = vec![0x00, 0xFF, 0x11, 0xFF, 0xFF, 0x22];
+    b.extend([0xFF; 4]);
+    b.push(0x33);
+    b.extend([0xFF; 8]);
+
+    assert_eq!(
+        Unsigned::decode(&*b).unwrap(),
+        Unsigned {
+            a: u8::MAX,
+            b: u16::MAX,
+            c: u32::MAX,
+            d: u64::MAX,
+        }
+    );
+
+    // a narrower type is sign-extended: BYTE 0xFF, SHORT 0xFFFF, INT 0xFFFFFFFF
+    for b in [
+        &[0x10, 0xFF][..],
+        &[0x20, 0xFF],
+        &[0x21, 0xFF, 0xFF],
+        &[0x30, 0xFF],
+        &[0x32, 0xFF, 0xFF, 0xFF, 0xFF],
+    ] {
+        assert!(matches!(
+            Unsigned::decode(b),
+            Err(jce::error::DecodeError::OutOfRange { value: -1, .. })
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+use jce::JceStruct;
+
+#[derive(JceStruct, Default, PartialEq, Debug)]
+struct Derived {
+    a: i32,
+    b: String,
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Custom {
+    a: i32,
+    b: String,
+}
+
+impl Default for Custom {
+    fn default() -> Self {
+        Self {
+            a: 114514,
+            b: "下北泽".into(),
+        }
+    }
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+#[jce(default)]
+struct Generated {
+    a: i32,
+    b: Vec<String>,
+}
+
+#[test]
+fn user_default() {
+    assert_eq!(Derived::decode(&[][..]).unwrap(), Derived::default());
+
+    // missing fields use the field's default, not the struct's
+    let custom = Custom::decode(&[0x00, 1][..]).unwrap();
+    assert_eq!(custom, Custom { a: 1, b: "".into() });
+}
+
+#[test]
+fn generated_default() {
+    assert_eq!(Generated::default(), Generated { a: 0, b: vec![] });
+}
+
+fn default_name() -> String {
+    "guest".into()
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+#[jce(default)]
+struct Declared {
+    #[jce(default = 3)]
+    retry: u8,
+    #[jce(default = -1)]
+    offset: i64,
+    #[jce(default = 1.5)]
+    ratio: f32,
+    #[jce(default = true)]
+    enabled: bool,
+    #[jce(default = "default_name")]
+    name: String,
+    plain: i32,
+}
+
+#[test]
+fn declared_default() {
+    let declared = Declared::decode(&[][..]).unwrap();
+    assert_eq!(
+        declared,
+        Declared {
+            retry: 3,
+            offset: -1,
+            ratio: 1.5,
+            enabled: true,
+            name: "guest".into(),
+            plain: 0,
+        }
+    );
+    assert_eq!(Declared::default(), declared);
+
+    // a present zero is not replaced by the default
+    let declared = Declared::decode(&[0x0C][..]).unwrap();
+    assert_eq!(declared.retry, 0);
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+#[jce(omit_defaults)]
+struct Omitted {
+    #[jce(default = 3)]
+    retry: u8,
+    #[jce(default = "default_name")]
+    name: String,
+    list: Vec<i32>,
+    #[jce(required)]
+    id: i32,
+}
+
+#[test]
+fn omit_defaults() {
+    let omitted = Omitted {
+        retry: 3,
+        name: "guest".into(),
+        list: vec![],
+        id: 0,
+    };
+
+    let mut b = vec![];
+    omitted.encode(&mut b).unwrap();
+
+    // only the required field is written
+    assert_eq!(b, [0x3C]);
+    assert_eq!(omitted.encoded_len(), Ok(b.len()));
+    assert_eq!(Omitted::decode(&*b).unwrap(), omitted);
+
+    let omitted = Omitted {
+        retry: 0,
+        list: vec![1],
+        ..omitted
+    };
+
+    let mut b = vec![];
+    omitted.encode(&mut b).unwrap();
+
+    assert_eq!(omitted.encoded_len(), Ok(b.len()));
+    assert_eq!(Omitted::decode(&*b).unwrap(), omitted);
+}
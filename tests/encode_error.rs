@@ -0,0 +1,209 @@
+use jce::bytes::{Buf, BufMut};
+use jce::error::{DecodeResult, EncodeError, EncodeResult};
+use jce::types::JceType;
+use jce::{JceStruct, JceStructRef, UnknownFields};
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+struct Person {
+    name: String,
+    age: u8,
+}
+
+#[test]
+fn insufficient_capacity() {
+    let person = Person {
+        name: "下北泽".into(),
+        age: 24,
+    };
+
+    let len = person.encoded_len().unwrap();
+
+    let mut b = [0u8; 4];
+    let err = person.encode(&mut b[..]).unwrap_err();
+    assert_eq!(
+        err,
+        EncodeError::InsufficientCapacity {
+            required: len,
+            available: 4,
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        format!("encode length {len} exceeds buffer capacity 4")
+    );
+
+    let mut b = vec![0u8; len];
+    person.encode(&mut b[..]).unwrap();
+    assert_eq!(Person::decode(&*b).unwrap(), person);
+}
+
+#[derive(JceStruct, Debug, Default)]
+struct Older {
+    name: String,
+    #[jce(unknown_fields)]
+    unknown: UnknownFields,
+}
+
+#[derive(JceStruct, Debug, Default)]
+struct Renamed<'a> {
+    nick: &'a str,
+    age: u8,
+    #[jce(unknown_fields)]
+    unknown: UnknownFields,
+}
+
+#[test]
+fn invalid_tag() {
+    let person = Person {
+        name: "下北泽".into(),
+        age: 24,
+    };
+
+    let mut b = vec![];
+    person.encode(&mut b).unwrap();
+
+    let older = Older::decode(&*b).unwrap();
+    assert_eq!(older.unknown.len(), 1);
+
+    let mut b = vec![];
+    older.encode(&mut b).unwrap();
+
+    // tag 1 is known to `Renamed`, it would be written twice
+    let renamed = Renamed {
+        nick: "1919",
+        age: 81,
+        unknown: older.unknown,
+    };
+    let err = renamed.encode(&mut vec![]).unwrap_err();
+    assert_eq!(err, EncodeError::InvalidTag(1));
+    assert_eq!(err.to_string(), "unknown field has tag 1 of the struct");
+}
+
+/// A value written as nothing, so a list of it costs no memory.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Unit;
+
+impl JceType for Unit {
+    fn read<B: Buf>(_: &mut B, _: u8, _: &'static str, _: &'static str) -> DecodeResult<Self> {
+        Ok(Self)
+    }
+
+    fn write<B: BufMut>(&self, _: &mut B, _: u8) {}
+
+    fn write_len(&self) -> EncodeResult<usize> {
+        Ok(0)
+    }
+}
+
+#[derive(JceStruct, Debug, Default)]
+struct Units {
+    units: Vec<Unit>,
+}
+
+/// A struct written as the bytes of its encoding.
+mod embedded {
+    use super::Units;
+    use jce::bytes::{Buf, BufMut};
+    use jce::error::{DecodeResult, EncodeResult};
+    use jce::types::JceType;
+    use jce::JceStruct;
+
+    pub fn read<B: Buf>(
+        buf: &mut B,
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<Units> {
+        let bytes = Vec::<u8>::read(buf, t, struct_name, field)?;
+        Units::decode(&*bytes)
+    }
+
+    pub fn write<B: BufMut>(units: &Units, buf: &mut B, tag: u8) {
+        let mut b = vec![];
+        units.encode_raw(&mut b);
+        b.write(buf, tag);
+    }
+
+    pub fn write_len(units: &Units) -> EncodeResult<usize> {
+        let mut b = vec![];
+        units.encode(&mut b)?;
+        b.write_len()
+    }
+}
+
+/// A string of at most 255 bytes.
+mod short {
+    use jce::bytes::{Buf, BufMut};
+    use jce::error::{DecodeResult, EncodeError, EncodeResult};
+    use jce::types::JceType;
+
+    pub fn read<B: Buf>(
+        buf: &mut B,
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<String> {
+        String::read(buf, t, struct_name, field)
+    }
+
+    pub fn write<B: BufMut>(s: &String, buf: &mut B, tag: u8) {
+        s.write(buf, tag);
+    }
+
+    pub fn write_len(s: &String) -> EncodeResult<usize> {
+        if s.len() > u8::MAX as usize {
+            return Err(EncodeError::BytesTooLong(s.len()));
+        }
+
+        s.write_len()
+    }
+}
+
+#[derive(JceStruct, Debug, Default)]
+struct Envelope {
+    #[jce(with = "embedded")]
+    units: Units,
+    #[jce(with = "short")]
+    name: String,
+}
+
+#[test]
+#[allow(clippy::uninit_vec)]
+fn nested() {
+    let len = i32::MAX as usize + 1;
+    let mut units = Vec::new();
+    // SAFETY: `Unit` is zero-sized and has no invalid values
+    unsafe { units.set_len(len) };
+
+    let envelope = Envelope {
+        units: Units { units },
+        name: "下北泽".into(),
+    };
+
+    // the error of the inner encode isn't lost
+    let mut b = vec![];
+    let err = envelope.encode(&mut b).unwrap_err();
+    assert_eq!(err, EncodeError::TooManyElements(len));
+    assert!(b.is_empty());
+    assert_eq!(envelope.encoded_len(), Err(err));
+}
+
+#[test]
+fn codec_error() {
+    let envelope = Envelope {
+        units: Units::default(),
+        name: "1".repeat(256),
+    };
+
+    let mut b = vec![];
+    let err = envelope.encode(&mut b).unwrap_err();
+    assert_eq!(err, EncodeError::BytesTooLong(256));
+    assert!(b.is_empty());
+
+    let envelope = Envelope {
+        name: "1".repeat(255),
+        ..envelope
+    };
+    envelope.encode(&mut b).unwrap();
+    assert_eq!(envelope.encoded_len(), Ok(b.len()));
+}
@@ -11,7 +11,7 @@ struct EncodedLen {
     tag_129: Alpha
 }
 
-#[derive(JceStruct)]
+#[derive(JceStruct, Default)]
 struct Alpha {
     f: i64,
     ba: Vec<i32>,
@@ -40,5 +40,22 @@ fn encoded_len() {
 
     encoded.encode(&mut buf).unwrap();
 
-    assert_eq!(encoded.encoded_len(), buf.len());
+    assert_eq!(encoded.encoded_len(), Ok(buf.len()));
+}
+
+#[test]
+fn bytes_len_boundary() {
+    #[derive(JceStruct)]
+    struct Str {
+        s: String,
+    }
+
+    for len in [0, 254, 255, 256] {
+        let s = Str { s: "1".repeat(len) };
+
+        let mut buf = vec![];
+        s.encode(&mut buf).unwrap();
+
+        assert_eq!(s.encoded_len(), Ok(buf.len()));
+    }
 }
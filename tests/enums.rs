@@ -0,0 +1,120 @@
+use jce::error::DecodeError;
+use jce::{JceStruct, JceType};
+
+#[derive(JceType, PartialEq, Debug, Default)]
+enum Color {
+    #[default]
+    Red = 1,
+    Green, // 2
+    Blue = 300,
+}
+
+#[derive(JceType, PartialEq, Debug, Default)]
+enum Platform {
+    #[default]
+    Android = 0,
+    Ios = -1,
+    #[jce(other)]
+    Unknown = 100,
+}
+
+#[derive(JceType, PartialEq, Debug, Default)]
+#[repr(i32)]
+enum Status {
+    #[default]
+    Ok = 0,
+    Failed = 1,
+    #[jce(other)]
+    Other(i32),
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Enums {
+    color: Color,
+    platform: Platform,
+    status: Status,
+    colors: Vec<Color>,
+}
+
+fn round_trip(enums: &Enums) -> Vec<u8> {
+    let mut b = vec![];
+    enums.encode(&mut b).unwrap();
+
+    assert_eq!(enums.encoded_len(), Ok(b.len()));
+    assert_eq!(&Enums::decode(&*b).unwrap(), enums);
+
+    b
+}
+
+#[test]
+fn enums() {
+    let b = round_trip(&Enums {
+        color: Color::Green,
+        platform: Platform::Android,
+        status: Status::Failed,
+        colors: vec![Color::Red, Color::Blue],
+    });
+
+    assert_eq!(
+        b,
+        [
+            0x00, 2,    // color: BYTE 2
+            0x1C, // platform: ZERO_TAG
+            0x20, 1, // status: BYTE 1
+            0x39, 0x00, 2, 0x00, 1, 0x01, 0x01, 0x2C, // colors
+        ]
+    );
+
+    round_trip(&Enums {
+        color: Color::Blue,
+        platform: Platform::Unknown,
+        status: Status::Other(-5),
+        colors: vec![],
+    });
+}
+
+#[test]
+fn unknown() {
+    // color: INT 5
+    assert!(matches!(
+        Enums::decode([0x02, 0, 0, 0, 5].as_ref()),
+        Err(DecodeError::UnknownDiscriminant {
+            struct_name: "Enums",
+            field: "color",
+            value: 5,
+        })
+    ));
+
+    // platform: BYTE 5, status: BYTE 5
+    let enums = Enums::decode([0x00, 1, 0x10, 5, 0x20, 5].as_ref()).unwrap();
+    assert_eq!(enums.platform, Platform::Unknown);
+    assert_eq!(enums.status, Status::Other(5));
+}
+
+#[derive(JceType, PartialEq, Debug)]
+#[repr(i32)]
+enum Code {
+    Success, // 0
+    #[jce(other)]
+    Other(i32), // 1
+    Retry,   // 2
+}
+
+#[test]
+fn other_between() {
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Reply {
+        #[jce(required)]
+        code: Code,
+    }
+
+    let reply = Reply { code: Code::Retry };
+
+    let mut b = vec![];
+    reply.encode(&mut b).unwrap();
+    assert_eq!(b, [0x00, 2]);
+    assert_eq!(Reply::decode(&*b).unwrap(), reply);
+
+    let reply = Reply::decode([0x00, 1].as_ref()).unwrap();
+    assert_eq!(reply.code, Code::Other(1));
+}
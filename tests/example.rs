@@ -10,7 +10,7 @@ struct Person {
     home: Home, // tag = 11
 }
 
-#[derive(JceStruct, PartialEq, Debug)]
+#[derive(JceStruct, PartialEq, Debug, Default)]
 struct Home {
     location: String, // tag = 0
 }
@@ -34,3 +34,25 @@ fn person() {
 
     assert_eq!(person, decode);
 }
+
+#[test]
+fn other_attributes() {
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Documented {
+        /// tag = 0
+        a: i32,
+        /// tag = 1
+        #[allow(dead_code)]
+        b: i32,
+    }
+
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Empty {}
+
+    let mut b = vec![];
+    Documented { a: 1, b: 2 }.encode(&mut b).unwrap();
+    assert_eq!(b, [0x00, 1, 0x10, 2]);
+    assert_eq!(Documented::decode(&*b).unwrap(), Documented { a: 1, b: 2 });
+
+    assert_eq!(Empty::decode(&*b).unwrap(), Empty {});
+}
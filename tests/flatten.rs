@@ -0,0 +1,144 @@
+use jce::types::Flatten;
+use jce::{JceStruct, JceStructRef, UnknownFields};
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+struct Head {
+    version: i16,
+    cmd: String,
+    seq: i32,
+    #[jce(tag = 4)]
+    flags: i64,
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Login {
+    #[jce(flatten)]
+    head: Head,
+    #[jce(tag = 5)]
+    uin: i64,
+    password: String,
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Plain {
+    version: i16,
+    cmd: String,
+    seq: i32,
+    #[jce(tag = 4)]
+    flags: i64,
+    uin: i64,
+    password: String,
+}
+
+fn login() -> Login {
+    Login {
+        head: Head {
+            version: 3,
+            cmd: "login".into(),
+            seq: 114514,
+            flags: 1 << 40,
+        },
+        uin: 1919810,
+        password: "下北泽".into(),
+    }
+}
+
+#[test]
+fn flatten() {
+    let login = login();
+
+    let mut b = vec![];
+    login.encode(&mut b).unwrap();
+    assert_eq!(login.encoded_len(), Ok(b.len()));
+
+    // the same as writing every field in place
+    let plain = Plain::decode(&*b).unwrap();
+    let mut b2 = vec![];
+    plain.encode(&mut b2).unwrap();
+    assert_eq!(b, b2);
+
+    assert_eq!(Login::decode(&*b).unwrap(), login);
+}
+
+#[test]
+fn nested_flatten() {
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Outer {
+        #[jce(flatten)]
+        login: Login,
+        #[jce(tag = 10)]
+        extra: Vec<i32>,
+        #[jce(unknown_fields)]
+        unknown: UnknownFields,
+    }
+
+    assert_eq!(
+        (0..=255)
+            .filter(|&t| Outer::TAGS.contains(t))
+            .collect::<Vec<_>>(),
+        [0, 1, 2, 4, 5, 6, 10]
+    );
+
+    let outer = Outer {
+        login: login(),
+        extra: vec![1, 2],
+        unknown: UnknownFields::new(),
+    };
+
+    let mut b = vec![];
+    outer.encode(&mut b).unwrap();
+    assert_eq!(outer.encoded_len(), Ok(b.len()));
+    assert_eq!(Outer::decode(&*b).unwrap(), outer);
+}
+
+#[test]
+fn out_of_order() {
+    let mut b = vec![];
+    login().encode(&mut b).unwrap();
+
+    // a LONG at tag 4 after the others
+    let mut moved = b.clone();
+    moved.extend([0x43, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    let login = Login::decode(&*moved).unwrap();
+    assert_eq!(login.head.flags, 1);
+}
+
+#[test]
+fn borrowed_and_generic() {
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Msg<'a> {
+        #[jce(flatten)]
+        head: Head,
+        #[jce(tag = 5)]
+        name: &'a str,
+    }
+
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Packet<H> {
+        #[jce(flatten)]
+        head: H,
+        #[jce(tag = 5)]
+        body: Vec<u8>,
+    }
+
+    let msg = Msg {
+        head: login().head,
+        name: "下北泽",
+    };
+
+    let mut b = vec![];
+    msg.encode(&mut b).unwrap();
+    assert_eq!(msg.encoded_len(), Ok(b.len()));
+    assert_eq!(Msg::decode_ref(&b).unwrap(), msg);
+
+    let packet = Packet {
+        head: login().head,
+        body: vec![1, 9, 1, 9],
+    };
+
+    let mut b = vec![];
+    packet.encode(&mut b).unwrap();
+    assert_eq!(packet.encoded_len(), Ok(b.len()));
+    assert_eq!(Packet::<Head>::decode(&*b).unwrap(), packet);
+}
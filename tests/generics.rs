@@ -0,0 +1,179 @@
+use jce::types::JceType;
+use jce::{JceStruct, JceStructRef};
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+struct Page<T> {
+    items: Vec<T>,
+    next: Option<String>,
+}
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+struct Item {
+    id: i32,
+    name: String,
+}
+
+#[test]
+fn page() {
+    let page = Page {
+        items: vec![
+            Item {
+                id: 1,
+                name: "下北泽".into(),
+            },
+            Item {
+                id: 2,
+                name: "114514".into(),
+            },
+        ],
+        next: Some("1919".into()),
+    };
+
+    let mut b = vec![];
+    page.encode(&mut b).unwrap();
+    assert_eq!(page.encoded_len(), Ok(b.len()));
+    assert_eq!(Page::<Item>::decode(&*b).unwrap(), page);
+
+    let page = Page {
+        items: vec![810i64],
+        next: None,
+    };
+    let mut b = vec![];
+    page.encode(&mut b).unwrap();
+    assert_eq!(Page::<i64>::decode(&*b).unwrap(), page);
+}
+
+#[derive(PartialEq, Debug)]
+struct NoDefault(i32);
+
+impl JceType for NoDefault {
+    fn read<B: jce::bytes::Buf>(
+        buf: &mut B,
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> jce::error::DecodeResult<Self> {
+        i32::read(buf, t, struct_name, field).map(Self)
+    }
+
+    fn write<B: jce::bytes::BufMut>(&self, buf: &mut B, tag: u8) {
+        self.0.write(buf, tag);
+    }
+
+    fn write_len(&self) -> jce::error::EncodeResult<usize> {
+        self.0.write_len()
+    }
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+#[jce(omit_defaults)]
+struct Pair<K, V> {
+    key: K,
+    #[jce(required)]
+    value: V,
+    // `Vec<V>: Default` holds for any `V`
+    rest: Vec<V>,
+}
+
+#[test]
+fn bare_params() {
+    let pair = Pair {
+        key: 0u8,
+        value: NoDefault(1),
+        rest: vec![],
+    };
+
+    let mut b = vec![];
+    pair.encode(&mut b).unwrap();
+    assert_eq!(pair.encoded_len(), Ok(b.len()));
+    assert_eq!(Pair::decode(&*b).unwrap(), pair);
+}
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+struct Borrowed<'a, T> {
+    name: &'a str,
+    value: T,
+    values: Vec<T>,
+}
+
+#[test]
+fn borrowed() {
+    let borrowed = Borrowed {
+        name: "下北泽",
+        value: 114514,
+        values: vec![1919, 810],
+    };
+
+    let mut b = vec![];
+    borrowed.encode(&mut b).unwrap();
+    assert_eq!(Borrowed::<i32>::decode_ref(&b).unwrap(), borrowed);
+
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Nested<'a, T> {
+        value: T,
+        values: Option<Vec<T>>,
+        borrowed: Borrowed<'a, i32>,
+    }
+
+    let nested = Nested {
+        value: "114514",
+        values: Some(vec!["1919", "810"]),
+        borrowed,
+    };
+
+    let mut b = vec![];
+    nested.encode(&mut b).unwrap();
+    assert_eq!(Nested::<&str>::decode_ref(&b).unwrap(), nested);
+}
+
+#[derive(JceStruct, PartialEq, Debug, Default, Clone, Copy)]
+#[jce(transparent)]
+struct Id<T>(T);
+
+pub trait Schema {
+    type Key;
+}
+
+struct Users;
+
+impl Schema for Users {
+    type Key = Id<i64>;
+}
+
+// `S::Key: JceType + Default`, not `S: JceType`
+#[derive(JceStruct)]
+struct Assoc<S: Schema> {
+    key: S::Key,
+    keys: Vec<S::Key>,
+}
+
+#[test]
+fn assoc() {
+    let assoc = Assoc::<Users> {
+        key: Id(114514),
+        keys: vec![Id(1919), Id(810)],
+    };
+
+    let mut b = vec![];
+    assoc.encode(&mut b).unwrap();
+
+    let decoded = Assoc::<Users>::decode(&*b).unwrap();
+    assert_eq!(decoded.key, assoc.key);
+    assert_eq!(decoded.keys, assoc.keys);
+}
+
+// inference would ask `S: JceType`
+#[derive(JceStruct)]
+#[jce(bound = "S::Key: JceType + Default")]
+struct Row<S: Schema> {
+    key: <S as Schema>::Key,
+}
+
+#[test]
+fn bound() {
+    let row = Row::<Users> { key: Id(1919810) };
+
+    let mut b = vec![];
+    row.encode(&mut b).unwrap();
+    assert_eq!(Row::<Users>::decode(&*b).unwrap().key, row.key);
+}
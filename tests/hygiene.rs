@@ -0,0 +1,78 @@
+use jce::JceStruct;
+
+mod facade {
+    pub use jce;
+}
+
+fn t() -> i32 {
+    114514
+}
+
+fn buf() -> String {
+    "下北泽".into()
+}
+
+// field names and expressions that used to clash with the generated code
+#[derive(JceStruct, PartialEq, Debug, Default)]
+struct Clash {
+    buf: Vec<u8>,
+    t: u8,
+    val: i32,
+    header: String,
+    to_end: bool,
+    tag: i64,
+    #[jce(default = "t")]
+    v: i32,
+    #[jce(skip = "buf()")]
+    skipped: String,
+}
+
+#[test]
+fn field_names() {
+    let clash = Clash {
+        buf: vec![1, 2, 3],
+        t: 4,
+        val: 5,
+        header: "6".into(),
+        to_end: true,
+        tag: 8,
+        v: 9,
+        skipped: buf(),
+    };
+
+    let mut b = vec![];
+    clash.encode(&mut b).unwrap();
+    assert_eq!(clash.encoded_len(), Ok(b.len()));
+    assert_eq!(Clash::decode(&*b).unwrap(), clash);
+
+    let decoded = Clash::decode(&[][..]).unwrap();
+    assert_eq!(decoded.v, t());
+    assert_eq!(decoded.skipped, buf());
+}
+
+#[derive(facade::jce::JceStruct, PartialEq, Debug)]
+#[jce(crate = facade::jce)]
+struct Renamed {
+    id: i32,
+    #[jce(required)]
+    kind: Kind,
+}
+
+#[derive(facade::jce::JceType, PartialEq, Debug)]
+#[jce(crate = "facade::jce")]
+enum Kind {
+    A = 1,
+    B = 2,
+}
+
+#[test]
+fn crate_path() {
+    let renamed = Renamed {
+        id: 1919,
+        kind: Kind::B,
+    };
+
+    let mut b = vec![];
+    renamed.encode(&mut b).unwrap();
+    assert_eq!(Renamed::decode(&*b).unwrap(), renamed);
+}
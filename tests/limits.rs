@@ -0,0 +1,209 @@
+use jce::de::DecodeLimits;
+use jce::error::DecodeError;
+use jce::{JceStruct, JceStructRef};
+
+#[derive(JceStruct, Debug)]
+struct Nested {
+    a: i64,
+    b: String,
+    c: Vec<Nested>,
+}
+
+fn nested(depth: usize) -> Vec<u8> {
+    // c: [ { c: [ { ... } ] } ]
+    let mut b = vec![];
+    for _ in 0..depth {
+        b.extend_from_slice(&[0x29, 0x00, 1, 0x0A]);
+    }
+    b
+}
+
+#[test]
+fn depth() {
+    assert!(Nested::decode(&*nested(10)).is_ok());
+
+    assert!(matches!(
+        Nested::decode(&*nested(100000)),
+        Err(DecodeError::DepthExceeded)
+    ));
+
+    let limits = DecodeLimits {
+        max_depth: 4,
+        ..Default::default()
+    };
+    assert!(Nested::decode_with_limits(&*nested(2), limits).is_ok());
+    assert!(matches!(
+        Nested::decode_with_limits(&*nested(3), limits),
+        Err(DecodeError::DepthExceeded)
+    ));
+
+    // unknown fields and values
+    let structs = [0x0A; 100000];
+    assert!(matches!(
+        jce::types::skip_field(&mut &structs[..], jce::types::STRUCT_START),
+        Err(DecodeError::DepthExceeded)
+    ));
+
+    let lists = [0x09, 0x00, 1].repeat(100000);
+    assert!(matches!(
+        jce::value::read_to_hashmap(&*lists),
+        Err(DecodeError::DepthExceeded)
+    ));
+}
+
+#[test]
+fn elements() {
+    let limits = DecodeLimits {
+        max_elements: 10,
+        ..Default::default()
+    };
+
+    // c: 1000 elements
+    assert!(matches!(
+        Nested::decode_with_limits([0x29, 0x01, 0x03, 0xE8].as_ref(), limits),
+        Err(DecodeError::TooManyElements(1000))
+    ));
+}
+
+#[test]
+fn bytes_len() {
+    let limits = DecodeLimits {
+        max_bytes_len: 4,
+        ..Default::default()
+    };
+
+    assert!(Nested::decode_with_limits([0x16, 4, b'1', b'1', b'4', b'5'].as_ref(), limits).is_ok());
+    assert!(matches!(
+        Nested::decode_with_limits([0x16, 5, b'1', b'1', b'4', b'5', b'1'].as_ref(), limits),
+        Err(DecodeError::BytesTooLong(5))
+    ));
+}
+
+#[derive(JceStruct, Debug)]
+struct Borrowed<'a> {
+    name: &'a str,
+    bytes: &'a [u8],
+}
+
+#[test]
+fn borrowed_bytes_len() {
+    let limits = DecodeLimits {
+        max_bytes_len: 4,
+        // borrowed bytes allocate nothing
+        max_alloc: 0,
+        ..Default::default()
+    };
+
+    let ok = [0x06, 4, b'1', b'1', b'4', b'5', 0x16, 4, 1, 9, 1, 9];
+    let borrowed = Borrowed::decode_ref_with_limits(&ok, limits).unwrap();
+    assert_eq!(borrowed.name, "1145");
+    assert_eq!(borrowed.bytes, [1, 9, 1, 9]);
+
+    assert!(matches!(
+        Borrowed::decode_ref_with_limits(&[0x06, 5, b'1', b'1', b'4', b'5', b'1'], limits),
+        Err(DecodeError::BytesTooLong(5))
+    ));
+    assert!(matches!(
+        Borrowed::decode_ref_with_limits(&[0x16, 5, 1, 9, 1, 9, 8], limits),
+        Err(DecodeError::BytesTooLong(5))
+    ));
+}
+
+#[test]
+fn alloc() {
+    let limits = DecodeLimits {
+        max_alloc: 10,
+        ..Default::default()
+    };
+
+    // the same field three times, 12 bytes in total
+    let bytes = [0x16, 4, 1, 1, 4, 5].repeat(3);
+
+    assert!(Nested::decode_with_limits(&bytes[..12], limits).is_ok());
+    assert!(matches!(
+        Nested::decode_with_limits(&*bytes, limits),
+        Err(DecodeError::AllocationExceeded)
+    ));
+
+    // the budget is per decode
+    assert!(Nested::decode_with_limits(&bytes[..12], limits).is_ok());
+}
+
+/// A struct embedded in bytes, decoded on its own.
+mod embedded {
+    use super::Nested;
+    use jce::bytes::{Buf, BufMut};
+    use jce::error::{DecodeResult, EncodeResult};
+    use jce::types::JceType;
+    use jce::JceStruct;
+
+    pub fn read<B: Buf>(
+        buf: &mut B,
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<Nested> {
+        let bytes = Vec::<u8>::read(buf, t, struct_name, field)?;
+        Nested::decode(&*bytes)
+    }
+
+    pub fn write<B: BufMut>(nested: &Nested, buf: &mut B, tag: u8) {
+        // checked by `write_len`
+        let mut b = vec![];
+        nested.encode_raw(&mut b);
+        b.write(buf, tag);
+    }
+
+    pub fn write_len(nested: &Nested) -> EncodeResult<usize> {
+        let mut b = vec![];
+        nested.encode(&mut b)?;
+        b.write_len()
+    }
+}
+
+#[derive(JceStruct, Debug)]
+struct Envelope {
+    #[jce(with = "embedded", required)]
+    inner: Nested,
+}
+
+fn envelope(inner: &[u8]) -> Vec<u8> {
+    let mut b = vec![0x06, inner.len() as u8];
+    b.extend_from_slice(inner);
+    b
+}
+
+#[test]
+fn nested_decode() {
+    // the limits of the outer decode apply to the embedded struct
+    let limits = DecodeLimits {
+        max_depth: 4,
+        ..Default::default()
+    };
+    assert!(Envelope::decode_with_limits(&*envelope(&nested(2)), limits).is_ok());
+    assert!(matches!(
+        Envelope::decode_with_limits(&*envelope(&nested(3)), limits),
+        Err(DecodeError::DepthExceeded)
+    ));
+
+    // and it is charged to the same budget, 18 bytes then 12
+    let inner = [0x16, 4, 1, 1, 4, 5].repeat(3);
+    let limits = DecodeLimits {
+        max_alloc: 20,
+        ..Default::default()
+    };
+    assert!(Nested::decode_with_limits(&*inner, limits).is_ok());
+    assert!(matches!(
+        Envelope::decode_with_limits(&*envelope(&inner), limits),
+        Err(DecodeError::AllocationExceeded)
+    ));
+
+    let limits = DecodeLimits {
+        max_alloc: 30,
+        ..Default::default()
+    };
+    assert!(Envelope::decode_with_limits(&*envelope(&inner), limits).is_ok());
+
+    // a decode on its own starts over
+    assert!(Nested::decode(&*nested(10)).is_ok());
+}
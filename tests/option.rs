@@ -10,7 +10,71 @@ struct Optional {
 
 #[test]
 fn option() {
-    let op = Optional::decode([12].as_ref()).unwrap();
+    // ZERO_TAG is a zero for numbers, and None written by older versions for others
+    let op = Optional::decode([12, 28].as_ref()).unwrap();
+
+    assert_eq!(op.a, Some(0));
+    assert!(op.b.is_none());
+
+    let op = Optional::decode([].as_ref()).unwrap();
 
     assert!(op.a.is_none());
+    assert!(op.b.is_none());
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Absent {
+    a: Option<i32>,
+    b: Option<String>,
+    c: Option<Home>,
+    #[jce(tag = "20")]
+    d: Option<i64>,
+    e: Vec<Option<i32>>,
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Home {
+    location: String,
+}
+
+#[test]
+fn absent() {
+    let absent = Absent {
+        a: None,
+        b: None,
+        c: None,
+        d: None,
+        e: vec![],
+    };
+
+    let mut b = vec![];
+    absent.encode(&mut b).unwrap();
+
+    // only the list is written
+    assert_eq!(b, [0xF9, 21, 0x0C]);
+    assert_eq!(absent.encoded_len(), Ok(b.len()));
+    assert_eq!(Absent::decode(&*b).unwrap(), absent);
+
+    let present = Absent {
+        a: Some(0),
+        b: Some("".into()),
+        c: Some(Home {
+            location: "下北泽".into(),
+        }),
+        d: Some(0),
+        e: vec![Some(1), None],
+    };
+
+    let mut b = vec![];
+    present.encode(&mut b).unwrap();
+
+    assert_eq!(present.encoded_len(), Ok(b.len()));
+    assert_eq!(
+        Absent::decode(&*b).unwrap(),
+        Absent {
+            // None in a list is written as ZERO_TAG
+            e: vec![Some(1), Some(0)],
+            ..present
+        }
+    );
 }
@@ -0,0 +1,55 @@
+use jce::error::DecodeError;
+use jce::JceStruct;
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Login {
+    #[jce(required)]
+    uin: i64,
+    #[jce(tag = "3", required)]
+    token: Vec<u8>,
+    device: String,
+    #[jce(tag = "10")]
+    #[jce(required)]
+    inner: Inner,
+}
+
+// no Default, it is always required
+#[derive(JceStruct, PartialEq, Debug)]
+struct Inner {
+    a: i32,
+}
+
+#[test]
+fn required() {
+    let login = Login {
+        uin: 0,
+        token: vec![],
+        device: "".into(),
+        inner: Inner { a: 0 },
+    };
+
+    let mut b = vec![];
+    login.encode(&mut b).unwrap();
+    assert_eq!(Login::decode(&*b).unwrap(), login);
+}
+
+#[test]
+fn missing() {
+    // uin only
+    let err = Login::decode(&[0x0C][..]).unwrap_err();
+    assert!(matches!(
+        err,
+        DecodeError::MissingField {
+            struct_name: "Login",
+            field: "token",
+            tag: 3,
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "missing required field token (tag 3) in struct Login"
+    );
+
+    let err = Login::decode(&[][..]).unwrap_err();
+    assert!(matches!(err, DecodeError::MissingField { tag: 0, .. }));
+}
@@ -0,0 +1,53 @@
+use jce::JceStruct;
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+struct NotJce;
+
+#[derive(JceStruct, Debug)]
+struct Cached<T> {
+    name: String,
+    #[jce(skip)]
+    cache: Cell<Option<usize>>,
+    // takes tag 1
+    id: i32,
+    #[jce(skip = "String::from(\"unknown\")")]
+    computed: String,
+    #[jce(skip)]
+    marker: PhantomData<T>,
+}
+
+#[test]
+fn skip() {
+    let cached = Cached::<NotJce> {
+        name: "下北泽".into(),
+        cache: Cell::new(Some(114514)),
+        id: 1919,
+        computed: "810".into(),
+        marker: PhantomData,
+    };
+
+    let mut b = vec![];
+    cached.encode(&mut b).unwrap();
+    assert_eq!(cached.encoded_len(), Ok(b.len()));
+
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Wire {
+        name: String,
+        id: i32,
+    }
+
+    assert_eq!(
+        Wire::decode(&*b).unwrap(),
+        Wire {
+            name: "下北泽".into(),
+            id: 1919,
+        }
+    );
+
+    let decoded = Cached::<NotJce>::decode(&*b).unwrap();
+    assert_eq!(decoded.name, cached.name);
+    assert_eq!(decoded.id, cached.id);
+    assert_eq!(decoded.cache.get(), None);
+    assert_eq!(decoded.computed, "unknown");
+}
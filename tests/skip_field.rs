@@ -40,3 +40,105 @@ fn skip_field() {
 
     println!("{:?}", A::decode(&*byte));
 }
+
+#[derive(JceStruct, Default)]
+struct Newer {
+    unknown: Level1, // tag = 0
+    field1: i8,      // tag = 1
+    field2: u8,      // tag = 2
+    #[jce(tag = "20")]
+    after: Level1,
+}
+
+#[derive(JceStruct, Default)]
+struct Level1 {
+    a: i32,
+    b: String,
+    inner: Level2,
+    #[jce(tag = "16")]
+    c: i64,
+}
+
+#[derive(JceStruct, Default)]
+struct Level2 {
+    x: Level3,
+    y: Vec<Level3>,
+    z: i16,
+}
+
+#[derive(JceStruct, Default)]
+struct Level3 {
+    s: String,
+    n: i32,
+}
+
+fn level1() -> Level1 {
+    Level1 {
+        a: 114514,
+        b: "unknown".into(),
+        inner: Level2 {
+            x: Level3 {
+                s: "x".into(),
+                n: 1,
+            },
+            y: vec![
+                Level3 {
+                    s: "y".into(),
+                    n: 0,
+                },
+                Level3 {
+                    s: "z".into(),
+                    n: -1,
+                },
+            ],
+            z: 1919,
+        },
+        c: 810,
+    }
+}
+
+#[test]
+fn skip_nested_struct() {
+    let newer = Newer {
+        unknown: level1(),
+        field1: 12,
+        field2: 253,
+        after: level1(),
+    };
+
+    let mut byte = BytesMut::new();
+    newer.encode(&mut byte).unwrap();
+
+    let a = A::decode(&*byte).unwrap();
+    assert_eq!(a.field1, 12);
+    assert_eq!(a.field2, 253);
+
+    // nested in A as an unknown field
+    #[derive(JceStruct)]
+    struct Wrapper {
+        a: Newer,
+        #[jce(tag = "5")]
+        tail: i32,
+    }
+
+    #[derive(JceStruct)]
+    struct OldWrapper {
+        #[jce(tag = "5")]
+        tail: i32,
+    }
+
+    let mut byte = BytesMut::new();
+    Wrapper { a: newer, tail: 7 }.encode(&mut byte).unwrap();
+    assert_eq!(OldWrapper::decode(&*byte).unwrap().tail, 7);
+}
+
+#[test]
+fn skip_struct_raw() {
+    let mut byte = BytesMut::new();
+    jce::types::JceType::write(&level1(), &mut byte, 0);
+    byte.extend_from_slice(&[1, 2, 3]);
+
+    let mut buf = &byte[1..];
+    jce::types::skip_field(&mut buf, jce::types::STRUCT_START).unwrap();
+    assert_eq!(buf, [1, 2, 3]);
+}
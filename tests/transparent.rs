@@ -0,0 +1,169 @@
+use jce::{JceStruct, JceStructRef};
+
+#[derive(JceStruct, PartialEq, Debug, Default, Clone, Copy)]
+#[jce(transparent)]
+struct Uin(i64);
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+#[jce(transparent)]
+struct Nick {
+    name: String,
+}
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+#[jce(transparent)]
+struct Name<'a>(&'a str);
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Friend {
+    uin: Uin,
+    nick: Nick,
+    group: Option<Uin>,
+    others: Vec<Uin>,
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Plain {
+    uin: i64,
+    nick: String,
+    group: Option<i64>,
+    others: Vec<i64>,
+}
+
+#[test]
+fn newtype() {
+    let friend = Friend {
+        uin: Uin(114514),
+        nick: Nick {
+            name: "下北泽".into(),
+        },
+        group: None,
+        others: vec![Uin(0), Uin(1 << 40)],
+    };
+
+    let plain = Plain {
+        uin: 114514,
+        nick: "下北泽".into(),
+        group: None,
+        others: vec![0, 1 << 40],
+    };
+
+    let mut b = vec![];
+    friend.encode(&mut b).unwrap();
+
+    let mut b2 = vec![];
+    plain.encode(&mut b2).unwrap();
+
+    // the same as the inner types
+    assert_eq!(b, b2);
+    assert_eq!(friend.encoded_len(), Ok(b.len()));
+    assert_eq!(Friend::decode(&*b).unwrap(), friend);
+}
+
+#[test]
+fn borrowed_newtype() {
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Borrowed<'a> {
+        name: Name<'a>,
+    }
+
+    let mut b = vec![];
+    Borrowed { name: Name("Jack") }.encode(&mut b).unwrap();
+
+    let borrowed = Borrowed::decode_ref(&b).unwrap();
+    assert_eq!(borrowed.name, Name("Jack"));
+    assert!(b.as_ptr_range().contains(&borrowed.name.0.as_ptr()));
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Tuple(i32, String, #[jce(tag = 5)] Vec<u8>, Uin);
+
+#[test]
+fn tuple_struct() {
+    let tuple = Tuple(1, "2".into(), vec![3], Uin(4));
+
+    let mut b = vec![];
+    tuple.encode(&mut b).unwrap();
+
+    assert_eq!(
+        b,
+        [
+            0x00, 1, // tag 0
+            0x16, 1, b'2', // tag 1
+            0x56, 1, 3, // tag 5
+            0x60, 4, // tag 6
+        ]
+    );
+    assert_eq!(tuple.encoded_len(), Ok(b.len()));
+    assert_eq!(Tuple::decode(&*b).unwrap(), tuple);
+}
+
+#[derive(JceStruct, PartialEq, Debug, Default, Clone, Copy)]
+#[jce(transparent)]
+struct Byte(u8);
+
+#[derive(JceStruct, PartialEq, Debug, Default, Clone, Copy)]
+#[jce(transparent)]
+struct Wrapped(Byte);
+
+#[test]
+fn newtype_list() {
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Lists {
+        bytes: Vec<Byte>,
+        wrapped: Vec<Wrapped>,
+        nested: Vec<Vec<Byte>>,
+        uins: Vec<Uin>,
+    }
+
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Plain {
+        bytes: Vec<u8>,
+        wrapped: Vec<u8>,
+        nested: Vec<Vec<u8>>,
+        uins: Vec<i64>,
+    }
+
+    let lists = Lists {
+        bytes: vec![Byte(1), Byte(200)],
+        wrapped: vec![Wrapped(Byte(3))],
+        nested: vec![vec![Byte(4)], vec![]],
+        uins: vec![Uin(5)],
+    };
+
+    let plain = Plain {
+        bytes: vec![1, 200],
+        wrapped: vec![3],
+        nested: vec![vec![4], vec![]],
+        uins: vec![5],
+    };
+
+    let mut b = vec![];
+    lists.encode(&mut b).unwrap();
+
+    let mut b2 = vec![];
+    plain.encode(&mut b2).unwrap();
+
+    // `Vec<Byte>` is bytes, like `Vec<u8>`
+    assert_eq!(b[0], 0x06);
+    assert_eq!(b, b2);
+    assert_eq!(lists.encoded_len(), Ok(b.len()));
+    assert_eq!(Lists::decode(&*b).unwrap(), lists);
+
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Borrowed<'a> {
+        names: Vec<Name<'a>>,
+        bytes: Vec<Byte>,
+    }
+
+    let borrowed = Borrowed {
+        names: vec![Name("114"), Name("514")],
+        bytes: vec![Byte(6)],
+    };
+
+    let mut b = vec![];
+    borrowed.encode(&mut b).unwrap();
+    assert_eq!(borrowed.encoded_len(), Ok(b.len()));
+    assert_eq!(b[b.len() - 3..], [0x16, 1, 6]);
+    assert_eq!(Borrowed::decode_ref(&b).unwrap(), borrowed);
+}
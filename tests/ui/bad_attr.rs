@@ -0,0 +1,33 @@
+use jce::JceStruct;
+
+#[derive(JceStruct)]
+#[jce(omit_default)]
+struct StructLevel {
+    a: i32,
+}
+
+#[derive(JceStruct)]
+struct IntTag {
+    #[jce(tag = 256)]
+    a: i32,
+}
+
+#[derive(JceStruct)]
+struct DuplicateKey {
+    #[jce(tag = 1, tag = 2)]
+    a: i32,
+}
+
+#[derive(JceStruct)]
+struct Syntax {
+    #[jce(tag 1)]
+    a: i32,
+}
+
+#[derive(JceStruct)]
+#[jce(bound = "T JceType")]
+struct Bound<T> {
+    a: T,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use jce::JceStruct;
+
+#[derive(JceStruct)]
+struct BadTag {
+    #[jce(tag = "256")]
+    a: i32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use jce::JceStruct;
+
+#[derive(JceStruct)]
+struct Duplicate {
+    #[jce(tag = "1")]
+    a: i32,
+    #[jce(tag = "1")]
+    b: i32,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use jce::JceType;
+
+#[derive(JceType)]
+enum Fields {
+    A,
+    B(i32),
+}
+
+#[derive(JceType)]
+enum Other {
+    #[jce(other)]
+    A,
+    #[jce(other)]
+    B,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use jce::JceStruct;
+
+#[derive(JceStruct, Default)]
+struct Head {
+    version: i16,
+    cmd: String,
+}
+
+#[derive(JceStruct)]
+struct Body {
+    #[jce(flatten)]
+    head: Head,
+    #[jce(tag = 1)]
+    uin: i64,
+}
+
+// borrowed structs are checked the same way
+#[derive(JceStruct)]
+struct Msg<'a> {
+    #[jce(flatten)]
+    head: Head,
+    #[jce(tag = 1)]
+    name: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use jce::bytes::{Buf, BufMut};
+use jce::error::{DecodeResult, EncodeResult};
+use jce::JceStruct;
+
+struct Manual {
+    a: i32,
+}
+
+// its tags aren't known, decoding couldn't find its fields
+impl JceStruct for Manual {
+    fn encode_raw<B: BufMut>(&self, buf: &mut B) {
+        jce::types::JceType::write(&self.a, buf, 0);
+    }
+
+    fn encoded_len(&self) -> EncodeResult<usize> {
+        jce::types::JceType::field_len(&self.a, 0)
+    }
+
+    fn decode_raw<B: Buf>(_: &mut B, _: bool) -> DecodeResult<Self> {
+        Ok(Self { a: 0 })
+    }
+}
+
+#[derive(JceStruct)]
+struct Parent {
+    #[jce(flatten)]
+    manual: Manual,
+    #[jce(tag = 5)]
+    b: i32,
+}
+
+fn main() {}
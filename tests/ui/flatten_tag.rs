@@ -0,0 +1,16 @@
+use jce::JceStruct;
+
+#[derive(JceStruct, Default)]
+struct Head {
+    version: i16,
+    cmd: String,
+}
+
+#[derive(JceStruct)]
+struct Body {
+    #[jce(flatten)]
+    head: Head,
+    uin: i64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use jce::JceStruct;
+
+#[derive(JceStruct)]
+struct RequiredDefault {
+    #[jce(required, default = 3)]
+    a: i32,
+}
+
+fn main() {}
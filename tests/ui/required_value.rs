@@ -0,0 +1,9 @@
+use jce::JceStruct;
+
+#[derive(JceStruct)]
+struct Required {
+    #[jce(required = "true")]
+    a: i32,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use jce::JceStruct;
+
+#[derive(JceStruct)]
+#[jce(transparent)]
+struct Pair(i32, String);
+
+#[derive(JceStruct)]
+#[jce(transparent)]
+struct Tagged(#[jce(tag = 1)] i32);
+
+#[derive(JceStruct)]
+struct Unit;
+
+#[derive(JceStruct)]
+enum Enum {
+    A,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use jce::JceStruct;
+
+#[derive(JceStruct)]
+struct SkipTag {
+    #[jce(skip, tag = "1")]
+    a: i32,
+}
+
+fn main() {}
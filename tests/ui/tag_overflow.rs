@@ -0,0 +1,10 @@
+use jce::JceStruct;
+
+#[derive(JceStruct)]
+struct Overflow {
+    #[jce(tag = "255")]
+    a: i32,
+    b: i32,
+}
+
+fn main() {}
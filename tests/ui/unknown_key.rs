@@ -0,0 +1,9 @@
+use jce::JceStruct;
+
+#[derive(JceStruct)]
+struct Unknown {
+    #[jce(tga = "1")]
+    a: i32,
+}
+
+fn main() {}
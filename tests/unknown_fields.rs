@@ -0,0 +1,117 @@
+use jce::{JceStruct, UnknownFields};
+use std::collections::HashMap;
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Newer {
+    id: i32,
+    name: String,
+    inner: Inner,
+    #[jce(tag = "5")]
+    token: Vec<u8>,
+    map: HashMap<String, Vec<i32>>,
+    #[jce(tag = "20")]
+    extra: String,
+    #[jce(tag = "30")]
+    flag: bool,
+}
+
+#[derive(JceStruct, PartialEq, Debug, Default)]
+struct Inner {
+    a: i64,
+    b: Vec<String>,
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Older {
+    id: i32,
+    #[jce(unknown_fields)]
+    unknown: UnknownFields,
+    #[jce(tag = "5")]
+    token: Vec<u8>,
+    #[jce(tag = "20")]
+    extra: String,
+}
+
+fn newer() -> Newer {
+    Newer {
+        id: 114514,
+        name: "下北泽".into(),
+        inner: Inner {
+            a: 1 << 40,
+            b: vec!["1919".into(), "810".into()],
+        },
+        token: vec![1, 1, 4],
+        map: HashMap::from([("114".into(), vec![5, 1, 4])]),
+        extra: "extra".into(),
+        flag: true,
+    }
+}
+
+#[test]
+fn round_trip() {
+    let newer = newer();
+
+    let mut b = vec![];
+    newer.encode(&mut b).unwrap();
+
+    let mut older = Older::decode(&*b).unwrap();
+    assert_eq!(
+        older.unknown.iter().map(|f| f.tag()).collect::<Vec<_>>(),
+        [1, 2, 6, 30]
+    );
+
+    // edit a known field and write it back
+    older.extra = "edited".into();
+
+    let mut b2 = vec![];
+    older.encode(&mut b2).unwrap();
+    assert_eq!(older.encoded_len(), Ok(b2.len()));
+
+    assert_eq!(
+        Newer::decode(&*b2).unwrap(),
+        Newer {
+            extra: "edited".into(),
+            ..newer
+        }
+    );
+
+    // unchanged, the bytes are the same
+    let older = Older::decode(&*b).unwrap();
+    let mut b2 = vec![];
+    older.encode(&mut b2).unwrap();
+    assert_eq!(b, b2);
+}
+
+#[test]
+fn only_unknown() {
+    #[derive(JceStruct, PartialEq, Debug)]
+    struct Opaque {
+        #[jce(unknown_fields)]
+        fields: UnknownFields,
+    }
+
+    let mut b = vec![];
+    newer().encode(&mut b).unwrap();
+
+    let opaque = Opaque::decode(&*b).unwrap();
+    assert_eq!(opaque.fields.len(), 7);
+
+    let mut b2 = vec![];
+    opaque.encode(&mut b2).unwrap();
+    assert_eq!(b, b2);
+}
+
+#[test]
+fn split() {
+    use bytes::Buf;
+
+    let mut b = vec![];
+    newer().encode(&mut b).unwrap();
+
+    let older = Older::decode(&*b).unwrap();
+
+    for i in 0..=b.len() {
+        let (front, back) = b.split_at(i);
+        assert_eq!(Older::decode(front.chain(back)).unwrap(), older);
+    }
+}
@@ -0,0 +1,112 @@
+use bytes::Bytes;
+use jce::JceStruct;
+use std::collections::HashMap;
+
+#[derive(JceStruct, Debug)]
+struct Packet {
+    a: i8,
+    b: i16,
+    c: i32,
+    d: i64,
+    e: f32,
+    f: f64,
+    g: String,
+    h: Bytes,
+    i: Vec<u8>,
+    j: [u8; 4],
+    k: HashMap<String, Vec<i32>>,
+    l: Vec<Inner>,
+    m: Option<Inner>,
+    #[jce(tag = "20")]
+    n: bool,
+}
+
+#[derive(JceStruct, Debug)]
+struct Inner {
+    s: String,
+    v: u64,
+}
+
+fn packet() -> Vec<u8> {
+    let p = Packet {
+        a: -1,
+        b: 300,
+        c: 70000,
+        d: 1 << 40,
+        e: 1.5,
+        f: -2.5,
+        g: "下北泽".into(),
+        h: Bytes::from_static(&[1, 2, 3]),
+        i: vec![0; 300],
+        j: [1, 1, 4, 5],
+        k: HashMap::from([("114".into(), vec![5, 1, 4])]),
+        l: vec![Inner {
+            s: "1919".into(),
+            v: 810,
+        }],
+        m: Some(Inner {
+            s: "".into(),
+            v: u64::MAX,
+        }),
+        n: true,
+    };
+
+    let mut b = vec![];
+    p.encode(&mut b).unwrap();
+    b
+}
+
+fn decode_all(bytes: &[u8]) {
+    let _ = Packet::decode(bytes);
+    let _ = jce::value::read_to_hashmap(bytes);
+    for t in 0..16 {
+        let _ = jce::types::skip_field(&mut &*bytes, t);
+    }
+}
+
+#[test]
+fn truncated() {
+    let bytes = packet();
+
+    for len in 0..bytes.len() {
+        decode_all(&bytes[..len]);
+    }
+}
+
+#[test]
+fn corrupted() {
+    let bytes = packet();
+
+    for i in 0..bytes.len() {
+        for b in [0x00, 0x0F, 0x7F, 0x80, 0xF0, 0xFF] {
+            let mut bytes = bytes.clone();
+            bytes[i] = b;
+            decode_all(&bytes);
+        }
+    }
+}
+
+#[test]
+fn huge_length() {
+    for bytes in [
+        // LIST of 2^63 elements
+        &[0x09, 0x03, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF][..],
+        // MAP of 2^63 entries
+        &[0x08, 0x03, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+        // LONG_BYTES of u32::MAX bytes
+        &[0x07, 0xFF, 0xFF, 0xFF, 0xFF],
+        // SINGLE_LIST of u64::MAX bytes
+        &[
+            0x0D, 0x00, 0x03, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        ],
+        // SINGLE_LIST of u64::MAX longs
+        &[
+            0x0D, 0x03, 0x03, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        ],
+    ] {
+        decode_all(bytes);
+
+        assert!(jce::value::read_to_hashmap(bytes).is_err());
+        assert!(jce::types::skip_field(&mut &bytes[1..], bytes[0] & 0xF).is_err());
+    }
+}
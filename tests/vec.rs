@@ -1,5 +1,6 @@
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use jce::JceStruct;
+use std::collections::HashMap;
 
 #[derive(JceStruct, Debug)]
 struct V {
@@ -7,7 +8,7 @@ struct V {
     b: B,
 }
 
-#[derive(JceStruct, Debug)]
+#[derive(JceStruct, Debug, Default)]
 struct B {
     b: Bytes,
     ca: i32,
@@ -31,3 +32,95 @@ fn vec() {
     println!("{:?}", bytes);
     println!("{:?}", V::decode(&*bytes).unwrap());
 }
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Lists {
+    strings: Vec<String>,
+    structs: Vec<Item>,
+    nested: Vec<Vec<i32>>,
+    maps: Vec<HashMap<String, i64>>,
+    blobs: Vec<Vec<u8>>,
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Item {
+    id: i32,
+    name: String,
+}
+
+#[test]
+fn generic_list() {
+    let lists = Lists {
+        strings: vec!["114".into(), "".into(), "514".into()],
+        structs: vec![
+            Item {
+                id: 0,
+                name: "a".into(),
+            },
+            Item {
+                id: 1919810,
+                name: "b".into(),
+            },
+        ],
+        nested: vec![vec![], vec![1, 2, 300]],
+        maps: vec![
+            HashMap::from([("k".to_string(), 1i64 << 40)]),
+            HashMap::new(),
+        ],
+        blobs: vec![vec![1, 2, 3], vec![]],
+    };
+
+    let mut bytes: Vec<u8> = Vec::new();
+    lists.encode(&mut bytes).unwrap();
+
+    assert_eq!(lists.encoded_len(), Ok(bytes.len()));
+    assert_eq!(Lists::decode(&*bytes).unwrap(), lists);
+}
+
+#[test]
+fn bytes_list() {
+    #[derive(JceStruct, Debug)]
+    struct Bs {
+        b: Vec<u8>,
+    }
+
+    // a LIST of BYTE still reads as bytes
+    let bs = Bs::decode([0x09, 0x00, 3, 0x00, 1, 0x0C, 0x00, 0xFF].as_ref()).unwrap();
+    assert_eq!(bs.b, [1, 0, 255]);
+
+    let mut bytes: Vec<u8> = Vec::new();
+    bs.encode(&mut bytes).unwrap();
+
+    // written as SHORT_BYTES
+    assert_eq!(bytes, [0x06, 3, 1, 0, 255]);
+}
+
+#[test]
+fn zero_copy() {
+    #[derive(JceStruct, Debug)]
+    struct Blob {
+        id: i32,
+        blob: Bytes,
+    }
+
+    let mut bytes = BytesMut::new();
+    Blob {
+        id: 1,
+        blob: vec![114; 1 << 20].into(),
+    }
+    .encode(&mut bytes)
+    .unwrap();
+
+    let bytes = bytes.freeze();
+    let range = bytes.as_ptr_range();
+
+    let blob = Blob::decode(bytes.clone()).unwrap();
+    assert_eq!(blob.blob.len(), 1 << 20);
+    assert!(range.contains(&blob.blob.as_ptr()));
+
+    let value = jce::value::read_to_hashmap(bytes.clone()).unwrap();
+    match &value[&1] {
+        jce::value::Value::Bytes(b) => assert!(range.contains(&b.as_ptr())),
+        v => panic!("{:?}", v),
+    }
+}
@@ -0,0 +1,94 @@
+use jce::JceStruct;
+use std::net::Ipv4Addr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Seconds since the epoch in a LONG.
+mod unix_secs {
+    use jce::bytes::{Buf, BufMut};
+    use jce::error::{DecodeError, DecodeResult, EncodeResult};
+    use jce::types::JceType;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    pub fn read<B: Buf>(
+        buf: &mut B,
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<SystemTime> {
+        let secs = i64::read(buf, t, struct_name, field)?;
+        u64::try_from(secs)
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+            .map_err(|_| DecodeError::OutOfRange {
+                struct_name,
+                field,
+                value: secs,
+            })
+    }
+
+    fn secs(time: &SystemTime) -> i64 {
+        time.duration_since(UNIX_EPOCH).unwrap().as_secs() as i64
+    }
+
+    pub fn write<B: BufMut>(time: &SystemTime, buf: &mut B, tag: u8) {
+        secs(time).write(buf, tag);
+    }
+
+    pub fn write_len(time: &SystemTime) -> EncodeResult<usize> {
+        secs(time).write_len()
+    }
+}
+
+/// An address stored as a little-endian INT.
+mod ipv4_le {
+    use jce::bytes::{Buf, BufMut};
+    use jce::error::{DecodeResult, EncodeResult};
+    use jce::types::JceType;
+    use std::net::Ipv4Addr;
+
+    pub fn read<B: Buf>(
+        buf: &mut B,
+        t: u8,
+        struct_name: &'static str,
+        field: &'static str,
+    ) -> DecodeResult<Ipv4Addr> {
+        let ip = u32::read(buf, t, struct_name, field)?;
+        Ok(Ipv4Addr::from(ip.to_le_bytes()))
+    }
+
+    pub fn write<B: BufMut>(ip: &Ipv4Addr, buf: &mut B, tag: u8) {
+        u32::from_le_bytes(ip.octets()).write(buf, tag);
+    }
+
+    pub fn write_len(ip: &Ipv4Addr) -> EncodeResult<usize> {
+        u32::from_le_bytes(ip.octets()).write_len()
+    }
+}
+
+fn unspecified() -> Ipv4Addr {
+    Ipv4Addr::UNSPECIFIED
+}
+
+#[derive(JceStruct, PartialEq, Debug)]
+struct Server {
+    #[jce(with = "ipv4_le", default = "unspecified")]
+    ip: Ipv4Addr,
+    port: u16,
+    #[jce(with = "unix_secs", required)]
+    login: SystemTime,
+}
+
+#[test]
+fn with() {
+    let server = Server {
+        ip: Ipv4Addr::new(127, 0, 0, 1),
+        port: 8080,
+        login: UNIX_EPOCH + Duration::from_secs(1 << 40),
+    };
+
+    let mut b = vec![];
+    server.encode(&mut b).unwrap();
+
+    assert_eq!(&b[..5], [0x02, 1, 0, 0, 127]);
+    assert_eq!(server.encoded_len(), Ok(b.len()));
+    assert_eq!(Server::decode(&*b).unwrap(), server);
+}